@@ -0,0 +1,497 @@
+//  ANY.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:54:04
+//  Last edited:
+//    18 Oct 2026, 13:10:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a [`AnyDatabase`] that selects its backend at runtime,
+//!   based on a config file.
+//
+
+use std::path::Path;
+
+use enum_debug::EnumDebug;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::common::{load_config_file, ExecuteResult, HealthReport};
+use crate::error::Error;
+#[cfg(feature = "mysql")]
+use crate::mysql;
+use crate::schema::Schema;
+use crate::spec::{Database as _, Executor as _};
+use crate::sql::{Dialect, Ident, Row, Statement, StatementCreateIndex, StatementCreateTable};
+#[cfg(feature = "sqlite")]
+use crate::sqlite;
+
+
+/***** HELPERS *****/
+/// Carries either an error of a backend or one of a user closure through the backend's methods.
+enum Wrapped<E> {
+    /// The backend failed. Boxed, as backend errors are large.
+    Backend(Box<Error>),
+    /// The user closure failed.
+    Func(E),
+}
+#[cfg(feature = "mysql")]
+impl<E> From<mysql::Error> for Wrapped<E> {
+    #[inline]
+    fn from(value: mysql::Error) -> Self { Self::Backend(Box::new(value.into())) }
+}
+#[cfg(feature = "sqlite")]
+impl<E> From<sqlite::Error> for Wrapped<E> {
+    #[inline]
+    fn from(value: sqlite::Error) -> Self { Self::Backend(Box::new(value.into())) }
+}
+impl<E: From<Error>> Wrapped<E> {
+    /// Returns the error this Wrapped carries as the user's error type.
+    #[inline]
+    fn into_inner(self) -> E {
+        match self {
+            Self::Backend(err) => (*err).into(),
+            Self::Func(err) => err,
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a config file that selects the backend to use with its `kind`, e.g.:
+/// ```yaml
+/// kind: sqlite
+/// path: ./data.db
+/// journal_mode: wal
+/// ```
+/// or
+/// ```yaml
+/// kind: mysql
+/// host: db.example.com
+/// database: app
+/// creds:
+///   kind: username_password_env
+///   username: app
+///   password_env: APP_DB_PASSWORD
+/// ```
+///
+/// Besides the `kind`, the fields are those of the backend's own config file (see [`mysql::ConfigFile`] and
+/// [`sqlite::ConfigFile`]).
+#[derive(Clone, Debug, Deserialize, EnumDebug, Serialize)]
+#[serde(tag = "kind")]
+pub enum ConfigFile {
+    /// Connect to a MySQL server.
+    #[cfg(feature = "mysql")]
+    #[serde(rename = "mysql")]
+    MySql(Box<mysql::ConfigFile>),
+    /// Open an SQLite database.
+    #[cfg(feature = "sqlite")]
+    #[serde(rename = "sqlite")]
+    Sqlite(sqlite::ConfigFile),
+}
+
+
+
+/// A database of which the backend is selected at runtime, such that a service can switch between backends without recompiling.
+///
+/// Because its [`Dialect`] is only known at runtime, this doesn't implement [`spec::Executor`](crate::spec::Executor); instead, it
+/// provides the same methods inherently. To use it with functions generic over [`spec::Database`](crate::spec::Database), match on it
+/// to get the backend's database.
+#[derive(EnumDebug)]
+pub enum AnyDatabase {
+    /// It's a MySQL database.
+    #[cfg(feature = "mysql")]
+    MySql(mysql::Database),
+    /// It's an SQLite database.
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlite::Database),
+}
+impl AnyDatabase {
+    /// Constructor for the AnyDatabase that connects to the backend described by the given [`ConfigFile`].
+    ///
+    /// # Arguments
+    /// - `config`: The [`ConfigFile`] that selects and configures the backend.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to the backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to connect to the backend.
+    pub fn from_config(config: ConfigFile) -> Result<Self, Error> {
+        match config {
+            #[cfg(feature = "mysql")]
+            ConfigFile::MySql(config) => match mysql::Database::from_config(*config) {
+                Ok(db) => Ok(Self::MySql(db)),
+                Err(err) => Err(Error::MySql { err }),
+            },
+            #[cfg(feature = "sqlite")]
+            ConfigFile::Sqlite(config) => match sqlite::Database::from_config(config, |_| Ok(())) {
+                Ok(db) => Ok(Self::Sqlite(db)),
+                Err(err) => Err(Error::Sqlite { err }),
+            },
+        }
+    }
+
+    /// Constructor for the AnyDatabase that connects to the backend described by the given config file.
+    ///
+    /// # Arguments
+    /// - `cfg_path`: The path to the [`ConfigFile`] that we'll be reading.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to the backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to read the given file or if we failed to connect to the backend.
+    pub fn from_path(cfg_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let cfg_path: &Path = cfg_path.as_ref();
+        info!("Initializing database by reading the options from '{}'", cfg_path.display());
+        match load_config_file::<ConfigFile>(cfg_path) {
+            Ok(config) => Self::from_config(config),
+            Err(err) => Err(Error::Config { err }),
+        }
+    }
+
+    /// Returns the SQL dialect spoken by the backend.
+    #[inline]
+    pub fn dialect(&self) -> Dialect {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(_) => Dialect::MySql,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => Dialect::Sqlite,
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` for some reason.
+    pub fn execute(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.execute(stmt).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.execute(stmt).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend as a prepared statement.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+    pub fn execute_prepared(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.execute_prepared(stmt).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.execute_prepared(stmt).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend and returns the resulting rows.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results.
+    pub fn query(&self, stmt: impl AsRef<Statement>) -> Result<Vec<Row>, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.query(stmt).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.query(stmt).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend and calls the given closure for every resulting row as it is read.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    /// - `func`: The closure to call for every [`Row`]. If it errors, no more rows are read.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results, or if `func` errors.
+    pub fn for_each_row<E>(&self, stmt: impl AsRef<Statement>, mut func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+    where
+        E: From<Error>,
+    {
+        let res: Result<(), Wrapped<E>> = match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.for_each_row(stmt, |row| func(row).map_err(Wrapped::Func)),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.for_each_row(stmt, |row| func(row).map_err(Wrapped::Func)),
+        };
+        res.map_err(Wrapped::into_inner)
+    }
+
+    /// Executes a raw SQL query on the backend, discarding any results.
+    ///
+    /// The query is sent as-is, so it must be written in the backend's `Self::dialect()`. Never build it from untrusted input.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `query` for some reason.
+    pub fn execute_raw(&self, query: &str) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.execute_raw(query).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.execute_raw(query).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Returns the names of all tables in the database, in alphabetical order.
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the backend for them.
+    pub fn tables(&self) -> Result<Vec<Ident>, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.tables().map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.tables().map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Reads the definition of a table back from the database.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to describe.
+    ///
+    /// # Returns
+    /// A [`StatementCreateTable`] that creates the table (without its indices; see `Self::indexes()`).
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the backend, or if the table doesn't exist or cannot be described.
+    pub fn describe_table(&self, table: &Ident) -> Result<StatementCreateTable, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.describe_table(table).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.describe_table(table).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Reads the indices on a table back from the database.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to read the indices of.
+    ///
+    /// # Returns
+    /// A list of [`StatementCreateIndex`]es that create the indices.
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the backend for them.
+    pub fn indexes(&self, table: &Ident) -> Result<Vec<StatementCreateIndex>, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.indexes(table).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.indexes(table).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Creates the given [`Schema`] in this database.
+    ///
+    /// # Arguments
+    /// - `schema`: The [`Schema`] to apply.
+    ///
+    /// # Errors
+    /// This function errors if the schema is invalid or if we failed to execute any of its statements.
+    pub fn apply_schema(&self, schema: &Schema) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.apply_schema(schema).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.apply_schema(schema).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Checks that the backend is reachable by running a trivial query on it.
+    ///
+    /// # Errors
+    /// This function errors if we failed to connect to the backend or to run the query.
+    pub fn ping(&self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.ping().map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.ping().map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Checks that the backend is reachable, and reports on it.
+    ///
+    /// # Returns
+    /// A [`HealthReport`] with the latency, version and connection pool of the backend.
+    ///
+    /// # Errors
+    /// This function errors if we failed to connect to the backend or to query it.
+    pub fn health(&self) -> Result<HealthReport, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.health().map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.health().map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Runs the given closure in a transaction on the backend.
+    ///
+    /// The transaction is committed if the closure returns [`Ok`], and rolled back if it returns [`Err`].
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`AnyTransaction`] to execute statements on.
+    ///
+    /// # Returns
+    /// The value returned by `func`.
+    ///
+    /// # Errors
+    /// This function errors if `func` errors, or if we failed to begin or commit the transaction.
+    pub fn transaction<T, E>(&self, func: impl FnOnce(&mut AnyTransaction) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<Error>,
+    {
+        let res: Result<T, Wrapped<E>> = match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(db) => db.transaction(|tx| func(&mut AnyTransaction::MySql(tx)).map_err(Wrapped::Func)),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(db) => db.transaction(|tx| func(&mut AnyTransaction::Sqlite(tx)).map_err(Wrapped::Func)),
+        };
+        res.map_err(Wrapped::into_inner)
+    }
+}
+#[cfg(feature = "mysql")]
+impl From<mysql::Database> for AnyDatabase {
+    #[inline]
+    fn from(value: mysql::Database) -> Self { Self::MySql(value) }
+}
+#[cfg(feature = "sqlite")]
+impl From<sqlite::Database> for AnyDatabase {
+    #[inline]
+    fn from(value: sqlite::Database) -> Self { Self::Sqlite(value) }
+}
+
+
+
+/// A transaction on an [`AnyDatabase`], as given by [`AnyDatabase::transaction()`].
+#[derive(EnumDebug)]
+pub enum AnyTransaction<'t, 'd> {
+    /// It's a transaction on a MySQL database.
+    #[cfg(feature = "mysql")]
+    MySql(&'t mut mysql::Transaction<'d>),
+    /// It's a transaction on an SQLite database.
+    #[cfg(feature = "sqlite")]
+    Sqlite(&'t mut sqlite::Transaction<'d>),
+}
+impl<'t, 'd> AnyTransaction<'t, 'd> {
+    /// Returns the SQL dialect spoken by the backend.
+    #[inline]
+    pub fn dialect(&self) -> Dialect {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(_) => Dialect::MySql,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => Dialect::Sqlite,
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] in this transaction.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` for some reason.
+    pub fn execute(&mut self, stmt: &Statement) -> Result<ExecuteResult, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(tx) => tx.execute(stmt).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(tx) => tx.execute(stmt).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] in this transaction as a prepared statement.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+    pub fn execute_prepared(&mut self, stmt: &Statement) -> Result<ExecuteResult, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(tx) => tx.execute_prepared(stmt).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(tx) => tx.execute_prepared(stmt).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Executes the given SQL [`Statement`] in this transaction and returns the resulting rows.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results.
+    pub fn query(&mut self, stmt: &Statement) -> Result<Vec<Row>, Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(tx) => tx.query(stmt).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(tx) => tx.query(stmt).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+
+    /// Executes a raw SQL query in this transaction, discarding any results.
+    ///
+    /// The query is sent as-is, so it must be written in the backend's `Self::dialect()`. Never build it from untrusted input.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `query` for some reason.
+    pub fn execute_raw(&mut self, query: &str) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "mysql")]
+            Self::MySql(tx) => tx.execute_raw(query).map_err(|err| Error::MySql { err }),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(tx) => tx.execute_raw(query).map_err(|err| Error::Sqlite { err }),
+        }
+    }
+}
@@ -0,0 +1,657 @@
+//  ASYNC MYSQL.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:40:01
+//  Last edited:
+//    18 Oct 2026, 14:32:02
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an async version of the MySQL [`Database`] for use in
+//!   [`tokio`] runtimes, using [`mysql_async`] as client.
+//
+
+use std::fmt::Display;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+pub use mysql_async as backend;
+use mysql_async::consts::ColumnType;
+use mysql_async::prelude::Queryable as _;
+#[cfg(any(feature = "mysql-native-tls", feature = "mysql-rustls-tls"))]
+use mysql_async::ClientIdentity;
+use mysql_async::{Conn, OptsBuilder, Params, Pool, PoolConstraints, PoolOpts, SslOpts};
+use num_traits::AsPrimitive;
+
+use crate::common::{ExecuteResult, HealthReport, PoolStats};
+pub use crate::mysql::Error;
+use crate::mysql::{check_supported, from_column, ConfigFile, ConnectionOptions, Credentials, TlsOptions, HEALTH_QUERY, PING_QUERY};
+use crate::retry::{RetryPolicy, Retryable};
+use crate::sql::{serialize_sql, serialize_sql_params, Dialect, Row, Statement, Value};
+
+
+/***** HELPERS *****/
+/// Converts the given [`TlsOptions`] to the async driver's [`SslOpts`].
+///
+/// # Arguments
+/// - `tls`: The [`TlsOptions`] to convert.
+///
+/// # Returns
+/// The [`SslOpts`] to connect with, or [`None`] if TLS is disabled.
+///
+/// # Errors
+/// This function errors if the options are invalid for the TLS backend compiled in.
+fn ssl_opts(tls: &TlsOptions) -> Result<Option<SslOpts>, Error> {
+    if !tls.check()? {
+        return Ok(None);
+    }
+    let opts: SslOpts = SslOpts::default()
+        .with_root_certs(tls.ca_cert.iter().map(|path| path.clone().into()).collect())
+        .with_danger_skip_domain_validation(tls.skip_domain_validation)
+        .with_danger_accept_invalid_certs(tls.accept_invalid_certs);
+
+    // Add the client identity if the backend supports it
+    #[cfg(feature = "mysql-rustls-tls")]
+    let opts: SslOpts = match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => opts.with_client_identity(Some(ClientIdentity::new(cert.clone().into(), key.clone().into()))),
+        _ => opts,
+    };
+    #[cfg(all(feature = "mysql-native-tls", not(feature = "mysql-rustls-tls")))]
+    let opts: SslOpts = match &tls.client_cert {
+        Some(archive) => {
+            let mut identity: ClientIdentity = ClientIdentity::new(archive.clone().into());
+            if let Some(password) = &tls.client_password {
+                identity = identity.with_password(password.clone());
+            }
+            opts.with_client_identity(Some(identity))
+        },
+        None => opts,
+    };
+    Ok(Some(opts))
+}
+
+/// Executes the given SQL [`Statement`] on the given connection.
+///
+/// # Arguments
+/// - `conn`: The [`Conn`] to execute on.
+/// - `stmt`: The [`Statement`] to execute.
+///
+/// # Returns
+/// An [`ExecuteResult`] with the number of rows affected by the statement and the `AUTO_INCREMENT` ID generated by it, if any.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `stmt` for some reason, or if it has a `RETURNING`-clause.
+async fn execute_on(conn: &mut Conn, stmt: &Statement) -> Result<ExecuteResult, Error> {
+    if stmt.is_noop() {
+        return Ok(ExecuteResult::default());
+    }
+    check_supported(stmt)?;
+
+    // Serialize directly and send
+    let query: String = serialize_sql(stmt, Dialect::MySql).to_string();
+    if let Err(err) = conn.query_drop(&query).await {
+        return Err(Error::AsyncExecuteFailed { query, err });
+    }
+    Ok(ExecuteResult { rows_affected: conn.affected_rows(), last_insert_id: conn.last_insert_id() })
+}
+
+/// Executes the given SQL [`Statement`] on the given connection as a prepared statement.
+///
+/// # Arguments
+/// - `conn`: The [`Conn`] to execute on.
+/// - `stmt`: The [`Statement`] to execute.
+///
+/// # Returns
+/// An [`ExecuteResult`] with the number of rows affected by the statement and the `AUTO_INCREMENT` ID generated by it, if any.
+///
+/// # Errors
+/// This function errors if we failed to prepare or execute the given `stmt` for some reason, or if it has a `RETURNING`-clause.
+async fn execute_prepared_on(conn: &mut Conn, stmt: &Statement) -> Result<ExecuteResult, Error> {
+    if stmt.is_noop() {
+        return Ok(ExecuteResult::default());
+    }
+    check_supported(stmt)?;
+
+    // Serialize with placeholders and send with the values bound
+    let (query, params): (String, Vec<Value>) = serialize_sql_params(stmt, Dialect::MySql);
+    let params: Params = if params.is_empty() { Params::Empty } else { Params::Positional(params.iter().map(mysql_async::Value::from).collect()) };
+    if let Err(err) = conn.exec_drop(&query, params).await {
+        return Err(Error::AsyncExecuteFailed { query, err });
+    }
+    Ok(ExecuteResult { rows_affected: conn.affected_rows(), last_insert_id: conn.last_insert_id() })
+}
+
+/// Executes the given SQL [`Statement`] on the given connection and returns the resulting rows.
+///
+/// # Arguments
+/// - `conn`: The [`Conn`] to execute on.
+/// - `stmt`: The [`Statement`] to execute.
+///
+/// # Returns
+/// A list of [`Row`]s produced by the statement.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `stmt` or read its results, or if it has a `RETURNING`-clause.
+async fn query_on(conn: &mut Conn, stmt: &Statement) -> Result<Vec<Row>, Error> {
+    if stmt.is_noop() {
+        return Ok(vec![]);
+    }
+    check_supported(stmt)?;
+
+    // Send as a prepared statement, such that we get typed values back
+    let query: String = serialize_sql(stmt, Dialect::MySql).to_string();
+    let mut res = match conn.exec_iter(query.as_str(), Params::Empty).await {
+        Ok(res) => res,
+        Err(err) => return Err(Error::AsyncQueryFailed { query, err }),
+    };
+
+    // Get the column names and types, as the latter tell us how to interpret some values
+    let (cols, types): (Arc<[String]>, Vec<ColumnType>) = match res.columns() {
+        Some(cols) => (cols.iter().map(|c| c.name_str().into_owned()).collect(), cols.iter().map(|c| c.column_type()).collect()),
+        None => (Arc::from([]), vec![]),
+    };
+    let rows = res.map(|row: mysql_async::Row| Row {
+        cols:   cols.clone(),
+        values: row.unwrap().into_iter().zip(&types).map(|(value, ty)| from_column(value, *ty)).collect(),
+    });
+    match rows.await {
+        Ok(rows) => Ok(rows),
+        Err(err) => Err(Error::AsyncQueryFailed { query, err }),
+    }
+}
+
+/// Executes a raw SQL query on the given connection, discarding any results.
+///
+/// # Arguments
+/// - `conn`: The [`Conn`] to execute on.
+/// - `query`: The SQL query to execute.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `query` for some reason.
+async fn execute_raw_on(conn: &mut Conn, query: &str) -> Result<(), Error> {
+    match conn.query_drop(query).await {
+        Ok(()) => Ok(()),
+        Err(err) => Err(Error::AsyncExecuteFailed { query: query.into(), err }),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A boxed future, as returned by the closures given to [`Database::transaction()`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn 'a + Send + Future<Output = T>>>;
+
+
+
+/// An async version of the MySQL [`crate::mysql::Database`].
+///
+/// Connections are taken from a [`mysql_async::Pool`], so it is cheap to clone and can be shared between tasks.
+///
+/// # Panics
+/// Every method panics if it is not called from within a [`tokio`] runtime.
+#[derive(Clone)]
+pub struct Database {
+    /// The MySQL connection pool we use to connect to the MySQL database.
+    pool:      Pool,
+    /// The minimum and maximum size of the pool, as reported by `Self::health()`.
+    pool_size: (usize, usize),
+    /// The name of the database we connect to.
+    database:  Arc<str>,
+    /// How statements that fail with transient errors are retried.
+    retry:     Arc<RetryPolicy>,
+}
+impl Database {
+    /// Constructor for the Database that initializes it pointing to a particular database.
+    ///
+    /// Note that no connection is made until the Database is first used.
+    ///
+    /// # Arguments
+    /// - `hostname`: The hostname of the MySQL endpoint to connect to.
+    /// - `port`: The port of the MySQL endpoint to connect to.
+    /// - `database`: The specific database to connect with.
+    /// - `creds`: A [`Credentials`] that describes how to authenticate ourselves to the server.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function errors if we failed to resolve the password in the given `creds`.
+    #[inline]
+    pub fn new(
+        hostname: impl AsRef<str>,
+        port: impl AsPrimitive<u16>,
+        database: impl AsRef<str>,
+        creds: impl AsRef<Credentials>,
+    ) -> Result<Self, Error> {
+        Self::with_options(hostname, port, database, creds, &ConnectionOptions::default())
+    }
+
+    /// Constructor for the Database that initializes it pointing to a particular database, with a tuned connection pool.
+    ///
+    /// Note that the async client does not support the `connect_timeout`, `read_timeout` and `write_timeout` options, which are
+    /// ignored with a warning. So is `check_on_startup`, as no connection is made until the Database is first used.
+    ///
+    /// # Arguments
+    /// - `hostname`: The hostname of the MySQL endpoint to connect to.
+    /// - `port`: The port of the MySQL endpoint to connect to.
+    /// - `database`: The specific database to connect with.
+    /// - `creds`: A [`Credentials`] that describes how to authenticate ourselves to the server.
+    /// - `options`: The [`ConnectionOptions`] that determine how connections are made and pooled.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function errors if the options are invalid or if we failed to resolve the password in the given `creds`.
+    pub fn with_options(
+        hostname: impl AsRef<str>,
+        port: impl AsPrimitive<u16>,
+        database: impl AsRef<str>,
+        creds: impl AsRef<Credentials>,
+        options: &ConnectionOptions,
+    ) -> Result<Self, Error> {
+        let hostname: &str = hostname.as_ref();
+        let port: u16 = port.as_();
+        let database: &str = database.as_ref();
+        let creds: &Credentials = creds.as_ref();
+        info!("Initializing async MySQL database to '{hostname}:{port}'");
+
+        // Prepare the options
+        debug!("Preparing connection options...");
+        let (username, password): (String, Option<String>) = creds.resolve()?;
+        let opts: OptsBuilder =
+            OptsBuilder::default().ip_or_hostname(hostname).tcp_port(port).db_name(Some(database)).user(Some(username)).pass(password);
+        if options.connect_timeout.is_some() || options.read_timeout.is_some() || options.write_timeout.is_some() {
+            warn!("Ignoring connection timeouts, as they are not supported by the async MySQL client");
+        }
+        if options.check_on_startup {
+            warn!("Ignoring startup check, as the async MySQL database doesn't connect until it is used (call `Database::health()` instead)");
+        }
+        let (min, max): (usize, usize) = options.pool_size()?;
+        let pool_opts: PoolOpts = PoolOpts::default()
+            .with_constraints(PoolConstraints::new(min, max).unwrap_or_default())
+            .with_abs_conn_ttl(options.max_lifetime.map(Duration::from_secs));
+        let opts: OptsBuilder = opts
+            .pool_opts(pool_opts)
+            .tcp_keepalive(options.tcp_keepalive)
+            .stmt_cache_size(options.stmt_cache_size)
+            .init(options.init.clone())
+            .socket(options.socket.as_ref().map(|path| path.to_string_lossy()))
+            .ssl_opts(ssl_opts(&options.tls)?);
+
+        // Create the connection pool itself, which doesn't connect yet
+        debug!("Creating async MySQL connection pool...");
+        Ok(Self { pool: Pool::new(opts), pool_size: (min, max), database: database.into(), retry: Arc::new(options.retry.clone()) })
+    }
+
+    /// Constructor for the Database that initializes it pointing to a particular database.
+    ///
+    /// Note that the file is read synchronously, which is fine at startup but should be avoided from within request handlers.
+    ///
+    /// # Arguments
+    /// - `cfg_path`: The path to the [`ConfigFile`] that we'll be reading.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to read the given file or if the options in it are invalid.
+    pub fn from_path(cfg_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let cfg_path: &Path = cfg_path.as_ref();
+        info!("Initializing async MySQL database by reading the options from '{}'", cfg_path.display());
+
+        // Attempt to read the credentials file
+        let config: ConfigFile = ConfigFile::from_path(cfg_path)?;
+
+        // Now call the normal initializer with these options
+        Self::from_config(config)
+    }
+
+    /// Constructor for the Database that initializes it pointing to the database in the given [`ConfigFile`].
+    ///
+    /// # Arguments
+    /// - `config`: The [`ConfigFile`] with the endpoint, credentials and options of the database.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if the options in it are invalid.
+    #[inline]
+    pub fn from_config(config: ConfigFile) -> Result<Self, Error> {
+        Self::with_options(config.host, config.port, config.database, config.creds, &config.options)
+    }
+
+    /// Constructor for the Database that initializes it pointing to the database in the given connection URL.
+    ///
+    /// See [`ConfigFile::from_url()`] for the format of the URL.
+    ///
+    /// # Arguments
+    /// - `url`: The connection URL to parse.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if the URL or the options in it are invalid. The password in `url` is redacted in any error.
+    pub fn from_url(url: impl AsRef<str>) -> Result<Self, Error> {
+        let config: ConfigFile = ConfigFile::from_url(url.as_ref())?;
+        info!("Initializing async MySQL database from URL to '{}:{}'", config.host, config.port);
+        Self::from_config(config)
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend.
+    ///
+    /// Any results of the query are discarded. See `Self::query()` to send a statement and return the rows.
+    ///
+    /// If the statement fails with a transient error, it is retried according to the [`RetryPolicy`] in the [`ConnectionOptions`].
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] with the number of rows affected by the statement and the `AUTO_INCREMENT` ID generated by it, if any.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection or to execute the given `stmt` for some reason.
+    #[inline]
+    pub async fn execute(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        let stmt: &Statement = stmt.as_ref();
+        self.retry.run_async(|| async move { execute_on(&mut self.get_conn().await?, stmt).await }).await
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend as a prepared statement.
+    ///
+    /// Any values in the statement are bound as parameters instead of serialized as literals. If the statement fails with a transient
+    /// error, it is retried according to the [`RetryPolicy`] in the [`ConnectionOptions`].
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] with the number of rows affected by the statement and the `AUTO_INCREMENT` ID generated by it, if any.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection or to prepare or execute the given `stmt` for some reason.
+    #[inline]
+    pub async fn execute_prepared(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        let stmt: &Statement = stmt.as_ref();
+        self.retry.run_async(|| async move { execute_prepared_on(&mut self.get_conn().await?, stmt).await }).await
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend and returns the resulting rows.
+    ///
+    /// If the statement fails with a transient error, it is retried according to the [`RetryPolicy`] in the [`ConnectionOptions`].
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection, execute the given `stmt` or read its results.
+    #[inline]
+    pub async fn query(&self, stmt: impl AsRef<Statement>) -> Result<Vec<Row>, Error> {
+        let stmt: &Statement = stmt.as_ref();
+        self.retry.run_async(|| async move { query_on(&mut self.get_conn().await?, stmt).await }).await
+    }
+
+    /// Executes a raw SQL query on the backend, discarding any results.
+    ///
+    /// This is an escape hatch for statements that cannot be expressed as a [`Statement`]. The query is sent as-is, so never build it
+    /// from untrusted input.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection or to execute the given `query` for some reason.
+    #[inline]
+    pub async fn execute_raw(&self, query: &str) -> Result<(), Error> { execute_raw_on(&mut self.get_conn().await?, query).await }
+
+    /// Runs the given closure in a transaction.
+    ///
+    /// The transaction is committed if the closure returns [`Ok`], and rolled back if it returns [`Err`]. If the returned future is
+    /// dropped before it completes, the connection is reset when it is returned to the pool, which rolls back the transaction.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// db.transaction(|tx| Box::pin(async move {
+    ///     tx.execute_prepared(&insert).await?;
+    ///     tx.query(&select).await
+    /// })).await?;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`Transaction`] to execute statements on and returns a [`BoxFuture`].
+    ///
+    /// # Returns
+    /// The value returned by `func`.
+    ///
+    /// # Errors
+    /// This function errors if `func` errors, or if we failed to get a connection or to begin or commit the transaction.
+    pub async fn transaction<T, E>(&self, func: impl for<'t> FnOnce(&'t mut Transaction) -> BoxFuture<'t, Result<T, E>>) -> Result<T, E>
+    where
+        E: From<Error>,
+    {
+        // Claim a connection for the duration of the transaction
+        let mut conn: Conn = self.get_conn().await?;
+        debug!("Beginning transaction");
+        if let Err(err) = conn.query_drop("START TRANSACTION").await {
+            return Err(Error::AsyncTransactionBegin { err }.into());
+        }
+
+        // Run the closure, then commit or rollback depending on its result
+        let mut tx: Transaction = Transaction { conn };
+        match func(&mut tx).await {
+            Ok(res) => {
+                debug!("Committing transaction");
+                if let Err(err) = tx.conn.query_drop("COMMIT").await {
+                    // Don't return the connection to the pool with the transaction still open
+                    if let Err(err) = tx.conn.query_drop("ROLLBACK").await {
+                        warn!("Failed to rollback transaction: {err}");
+                    }
+                    return Err(Error::AsyncTransactionCommit { err }.into());
+                }
+                Ok(res)
+            },
+            Err(err) => {
+                debug!("Rolling back transaction");
+                if let Err(err) = tx.conn.query_drop("ROLLBACK").await {
+                    warn!("Failed to rollback transaction: {err}");
+                }
+                Err(err)
+            },
+        }
+    }
+
+    /// Runs the given closure in a transaction, and runs it again in a new transaction if it fails with a transient error.
+    ///
+    /// Unlike `Self::transaction()`, the closure may be called multiple times, so it should not have side effects outside of the
+    /// transaction. Whether and how often it is retried is determined by the [`RetryPolicy`] in the [`ConnectionOptions`].
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`Transaction`] to execute statements on and returns a [`BoxFuture`].
+    ///
+    /// # Returns
+    /// The value returned by `func` in the attempt that succeeded.
+    ///
+    /// # Errors
+    /// This function errors if the last attempt fails, or if any attempt fails with an error that is not retried. Attempts may fail
+    /// because `func` errors or because we failed to get a connection or to begin or commit the transaction.
+    pub async fn transaction_with_retry<T, E>(
+        &self,
+        mut func: impl for<'t> FnMut(&'t mut Transaction) -> BoxFuture<'t, Result<T, E>>,
+    ) -> Result<T, E>
+    where
+        E: From<Error> + Display + Retryable,
+    {
+        // Not `RetryPolicy::run_async()`, as every attempt borrows `func` for as long as its future lives
+        let mut attempt: u32 = 1;
+        loop {
+            let err: E = match self.transaction(&mut func).await {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+            match self.retry.backoff_after(attempt, &err) {
+                Some(backoff) => tokio::time::sleep(backoff).await,
+                None => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Checks that the server is reachable by running a trivial query on it.
+    ///
+    /// Unlike statements, this is never retried.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection or to run the query.
+    pub async fn ping(&self) -> Result<(), Error> {
+        match self.get_conn().await?.query_drop(PING_QUERY).await {
+            Ok(()) => Ok(()),
+            Err(err) => Err(Error::AsyncQueryFailed { query: PING_QUERY.into(), err }),
+        }
+    }
+
+    /// Checks that the server is reachable and that the database exists, and reports on it.
+    ///
+    /// Unlike statements, this is never retried.
+    ///
+    /// # Returns
+    /// A [`HealthReport`] with the time it took to get a connection and query the server, the server's version and the configured size
+    /// of the pool. The async MySQL driver doesn't report idle connections, so those are not reported.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection or to query the server, or if the database doesn't exist.
+    pub async fn health(&self) -> Result<HealthReport, Error> {
+        let start: Instant = Instant::now();
+        let mut conn: Conn = match self.get_conn().await {
+            Ok(conn) => conn,
+            Err(err) if err.is_database_missing() => return Err(Error::DatabaseMissing { database: self.database.to_string() }),
+            Err(err) => return Err(err),
+        };
+        let (server_version, current): (String, Option<String>) = match conn.query_first(HEALTH_QUERY).await {
+            Ok(row) => row.unwrap_or_default(),
+            Err(err) => return Err(Error::AsyncQueryFailed { query: HEALTH_QUERY.into(), err }),
+        };
+        let latency: Duration = start.elapsed();
+        if current.as_deref() != Some(&*self.database) {
+            return Err(Error::DatabaseMissing { database: self.database.to_string() });
+        }
+
+        let (min, max): (usize, usize) = self.pool_size;
+        Ok(HealthReport { latency, server_version, pool: PoolStats { min_connections: min, max_connections: max, idle_connections: None } })
+    }
+
+    /// Gets a connection from the internal pool.
+    ///
+    /// # Returns
+    /// A [`Conn`] that returns itself to the pool when dropped.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a connection.
+    #[inline]
+    async fn get_conn(&self) -> Result<Conn, Error> {
+        match self.pool.get_conn().await {
+            Ok(conn) => Ok(conn),
+            Err(err) => Err(Error::AsyncConnectionGet { err }),
+        }
+    }
+}
+
+
+
+/// A transaction on an async MySQL [`Database`], as given by [`Database::transaction()`].
+pub struct Transaction {
+    /// The connection on which the transaction runs.
+    conn: Conn,
+}
+impl Transaction {
+    /// Executes the given SQL [`Statement`] as part of the transaction.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` for some reason.
+    #[inline]
+    pub async fn execute(&mut self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> { execute_on(&mut self.conn, stmt.as_ref()).await }
+
+    /// Executes the given SQL [`Statement`] as a prepared statement as part of the transaction.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+    #[inline]
+    pub async fn execute_prepared(&mut self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        execute_prepared_on(&mut self.conn, stmt.as_ref()).await
+    }
+
+    /// Executes the given SQL [`Statement`] as part of the transaction and returns the resulting rows.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results.
+    #[inline]
+    pub async fn query(&mut self, stmt: impl AsRef<Statement>) -> Result<Vec<Row>, Error> { query_on(&mut self.conn, stmt.as_ref()).await }
+
+    /// Executes a raw SQL query as part of the transaction, discarding any results.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `query` for some reason.
+    #[inline]
+    pub async fn execute_raw(&mut self, query: &str) -> Result<(), Error> { execute_raw_on(&mut self.conn, query).await }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tls_ssl_opts_disabled() {
+        assert!(ssl_opts(&TlsOptions::default()).unwrap().is_none());
+    }
+
+    #[test]
+    #[cfg(any(feature = "mysql-native-tls", feature = "mysql-rustls-tls"))]
+    fn tls_ssl_opts() {
+        let tls: TlsOptions =
+            TlsOptions { enabled: true, ca_cert: Some("/certs/ca.pem".into()), skip_domain_validation: true, ..Default::default() };
+        let opts: SslOpts = ssl_opts(&tls).unwrap().unwrap();
+        assert_eq!(opts.root_certs(), [std::path::PathBuf::from("/certs/ca.pem").into()]);
+        assert!(opts.skip_domain_validation());
+        assert!(!opts.accept_invalid_certs());
+        assert!(opts.client_identity().is_none());
+    }
+}
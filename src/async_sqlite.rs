@@ -0,0 +1,398 @@
+//  ASYNC SQLITE.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:40:01
+//  Last edited:
+//    18 Oct 2026, 13:10:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an async version of the SQLite [`Database`] for use in
+//!   [`tokio`] runtimes.
+//!
+//!   SQLite itself is blocking, so all work is offloaded to the runtime's
+//!   blocking pool.
+//
+
+use std::fmt::Display;
+use std::panic;
+use std::path::PathBuf;
+
+use log::info;
+use tokio::task;
+
+use crate::common::{ExecuteResult, HealthReport};
+use crate::retry::Retryable;
+use crate::schema::Schema;
+use crate::spec::Database as _;
+use crate::sql::{Ident, Row, Statement, StatementCreateIndex, StatementCreateTable};
+pub use crate::sqlite::Error;
+use crate::sqlite::{self, ConnectionOptions, Transaction};
+
+
+/***** HELPERS *****/
+/// Runs the given closure on the runtime's blocking pool.
+///
+/// # Arguments
+/// - `func`: The closure to run.
+///
+/// # Returns
+/// The value returned by `func`.
+///
+/// # Errors
+/// This function errors if `func` errors, or if the task was cancelled before it ran.
+///
+/// # Panics
+/// This function panics if `func` panics, such that panics propagate like they would for the blocking [`sqlite::Database`].
+async fn blocking<T, E>(func: impl 'static + Send + FnOnce() -> Result<T, E>) -> Result<T, E>
+where
+    T: 'static + Send,
+    E: 'static + Send + From<Error>,
+{
+    match task::spawn_blocking(func).await {
+        Ok(res) => res,
+        Err(err) if err.is_panic() => panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(Error::TaskCancelled { err }.into()),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// An async version of the SQLite [`sqlite::Database`].
+///
+/// Every call is run on the [`tokio`] blocking pool, so it never stalls the runtime. Like the blocking Database, it is cheap to clone
+/// and can be shared between tasks; see [`sqlite::Database`] for how concurrent reads and writes are handled.
+///
+/// # Panics
+/// Every method panics if it is not called from within a [`tokio`] runtime.
+#[derive(Clone)]
+pub struct Database {
+    /// The blocking database that does the actual work.
+    db: sqlite::Database,
+}
+impl Database {
+    /// Constructor for the Database that initializes it pointing to a particular database.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the database file.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it. It runs on the blocking pool.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to connect to the given endpoint.
+    #[inline]
+    pub async fn new<F>(path: impl Into<PathBuf>, init: F) -> Result<Self, Error>
+    where
+        F: 'static + Send + FnOnce(&sqlite::Database) -> Result<(), Error>,
+    {
+        Self::with_options(path, ConnectionOptions::default(), init).await
+    }
+
+    /// Constructor for the Database that initializes it pointing to a particular database, opened and tuned with the given options.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the database file.
+    /// - `options`: The [`ConnectionOptions`] that determine how the database is opened and which pragmas are set on it.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it. It runs on the blocking pool.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to connect to the given endpoint or to set any of the pragmas.
+    pub async fn with_options<F>(path: impl Into<PathBuf>, options: ConnectionOptions, init: F) -> Result<Self, Error>
+    where
+        F: 'static + Send + FnOnce(&sqlite::Database) -> Result<(), Error>,
+    {
+        let path: PathBuf = path.into();
+        info!("Initializing async SQLite database at '{}'", path.display());
+        let db: sqlite::Database = blocking(move || sqlite::Database::with_options(path, &options, init)).await?;
+        Ok(Self { db })
+    }
+
+    /// Constructor for the Database that initializes it pointing to a particular database.
+    ///
+    /// # Arguments
+    /// - `cfg_path`: The path to the [`sqlite::ConfigFile`] that we'll be reading.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it. It runs on the blocking pool.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to read the given file or if we failed to connect to the given endpoint.
+    pub async fn from_path<F>(cfg_path: impl Into<PathBuf>, init: F) -> Result<Self, Error>
+    where
+        F: 'static + Send + FnOnce(&sqlite::Database) -> Result<(), Error>,
+    {
+        let cfg_path: PathBuf = cfg_path.into();
+        let db: sqlite::Database = blocking(move || sqlite::Database::from_path(cfg_path, init)).await?;
+        Ok(Self { db })
+    }
+
+    /// Constructor for the Database that initializes it pointing to the database in the given [`sqlite::ConfigFile`].
+    ///
+    /// # Arguments
+    /// - `config`: The [`sqlite::ConfigFile`] with the path and options of the database.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it. It runs on the blocking pool.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to connect to the given endpoint.
+    pub async fn from_config<F>(config: sqlite::ConfigFile, init: F) -> Result<Self, Error>
+    where
+        F: 'static + Send + FnOnce(&sqlite::Database) -> Result<(), Error>,
+    {
+        let db: sqlite::Database = blocking(move || sqlite::Database::from_config(config, init)).await?;
+        Ok(Self { db })
+    }
+
+    /// Constructor for the Database that initializes it pointing to the database in the given connection URL.
+    ///
+    /// See [`sqlite::ConfigFile::from_url()`] for the format of the URL.
+    ///
+    /// # Arguments
+    /// - `url`: The connection URL to parse.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it. It runs on the blocking pool.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if the URL is invalid or if we failed to connect to the given endpoint.
+    pub async fn from_url<F>(url: impl Into<String>, init: F) -> Result<Self, Error>
+    where
+        F: 'static + Send + FnOnce(&sqlite::Database) -> Result<(), Error>,
+    {
+        let url: String = url.into();
+        let db: sqlite::Database = blocking(move || sqlite::Database::from_url(url, init)).await?;
+        Ok(Self { db })
+    }
+
+    /// Returns the blocking [`sqlite::Database`] that does the actual work.
+    ///
+    /// This shares its connections with this Database. Note that calling it directly blocks the current thread.
+    ///
+    /// # Returns
+    /// A reference to the [`sqlite::Database`].
+    #[inline]
+    pub fn blocking(&self) -> &sqlite::Database { &self.db }
+
+    /// Runs the given closure on the blocking [`sqlite::Database`] in the runtime's blocking pool.
+    ///
+    /// This can be used for anything not covered by the other methods, or to run multiple statements without going back and forth
+    /// between the runtime and the blocking pool.
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run.
+    ///
+    /// # Returns
+    /// The value returned by `func`.
+    ///
+    /// # Errors
+    /// This function errors if `func` errors.
+    pub async fn run<T, E>(&self, func: impl 'static + Send + FnOnce(&sqlite::Database) -> Result<T, E>) -> Result<T, E>
+    where
+        T: 'static + Send,
+        E: 'static + Send + From<Error>,
+    {
+        let db: sqlite::Database = self.db.clone();
+        blocking(move || func(&db)).await
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend.
+    ///
+    /// See [`sqlite::Database::execute()`] for more information.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] with the number of rows affected by the statement and, for inserts, the `ROWID` of the last inserted row.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` for some reason.
+    pub async fn execute(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        let stmt: Statement = stmt.as_ref().clone();
+        self.run(move |db| db.execute(stmt)).await
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend as a prepared statement.
+    ///
+    /// See [`sqlite::Database::execute_prepared()`] for more information.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] with the number of rows affected by the statement and, for inserts, the `ROWID` of the last inserted row.
+    ///
+    /// # Errors
+    /// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+    pub async fn execute_prepared(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        let stmt: Statement = stmt.as_ref().clone();
+        self.run(move |db| db.execute_prepared(stmt)).await
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend and returns the resulting rows.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results.
+    pub async fn query(&self, stmt: impl AsRef<Statement>) -> Result<Vec<Row>, Error> {
+        let stmt: Statement = stmt.as_ref().clone();
+        self.run(move |db| db.query(stmt)).await
+    }
+
+    /// Executes a raw SQL query on the backend, discarding any results.
+    ///
+    /// This is an escape hatch for statements that cannot be expressed as a [`Statement`]. The query is sent as-is, so never build it
+    /// from untrusted input.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute. It may contain multiple statements.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `query` for some reason.
+    pub async fn execute_raw(&self, query: impl Into<String>) -> Result<(), Error> {
+        let query: String = query.into();
+        self.run(move |db| db.execute_raw(&query)).await
+    }
+
+    /// Returns the names of all tables in the database, in alphabetical order.
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the database for them.
+    #[inline]
+    pub async fn tables(&self) -> Result<Vec<Ident>, Error> { self.run(|db| db.tables()).await }
+
+    /// Reads the definition of a table back from the database.
+    ///
+    /// See [`sqlite::Database::describe_table()`] for more information.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to describe.
+    ///
+    /// # Returns
+    /// A [`StatementCreateTable`] that creates the table (without its indices; see `Self::indexes()`).
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the database, or if the table doesn't exist or cannot be described.
+    pub async fn describe_table(&self, table: impl Into<Ident>) -> Result<StatementCreateTable, Error> {
+        let table: Ident = table.into();
+        self.run(move |db| db.describe_table(&table)).await
+    }
+
+    /// Reads the indices on a table back from the database.
+    ///
+    /// See [`sqlite::Database::indexes()`] for more information.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to read the indices of.
+    ///
+    /// # Returns
+    /// A list of [`StatementCreateIndex`]es that create the indices.
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the database for them.
+    pub async fn indexes(&self, table: impl Into<Ident>) -> Result<Vec<StatementCreateIndex>, Error> {
+        let table: Ident = table.into();
+        self.run(move |db| db.indexes(&table)).await
+    }
+
+    /// Creates the given [`Schema`] in this database.
+    ///
+    /// See [`sqlite::Database::apply_schema()`] for more information.
+    ///
+    /// # Arguments
+    /// - `schema`: The [`Schema`] to apply.
+    ///
+    /// # Errors
+    /// This function errors if the schema is invalid or if we failed to execute any of its statements.
+    pub async fn apply_schema(&self, schema: Schema) -> Result<(), Error> { self.run(move |db| db.apply_schema(&schema)).await }
+
+    /// Checks that the database can be read by running a trivial query on it.
+    ///
+    /// See [`sqlite::Database::ping()`] for more information.
+    ///
+    /// # Errors
+    /// This function errors if we failed to run the query.
+    #[inline]
+    pub async fn ping(&self) -> Result<(), Error> { self.run(|db| db.ping()).await }
+
+    /// Checks that the database can be read, and reports on it.
+    ///
+    /// See [`sqlite::Database::health()`] for more information. Note that the latency is that of the query only, and doesn't include
+    /// the time it took to get to the blocking pool.
+    ///
+    /// # Returns
+    /// A [`HealthReport`] with the time it took to run a query, the version of SQLite and the connections in use.
+    ///
+    /// # Errors
+    /// This function errors if we failed to run the query.
+    #[inline]
+    pub async fn health(&self) -> Result<HealthReport, Error> { self.run(|db| db.health()).await }
+
+    /// Runs the given closure in a transaction.
+    ///
+    /// The closure is given the blocking [`Transaction`] and runs on the blocking pool, holding the writer until it is done. The
+    /// transaction is committed if the closure returns [`Ok`], and rolled back if it returns [`Err`].
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`Transaction`] to execute statements on.
+    ///
+    /// # Returns
+    /// The value returned by `func`.
+    ///
+    /// # Errors
+    /// This function errors if `func` errors, or if we failed to begin or commit the transaction.
+    pub async fn transaction<T, E>(&self, func: impl 'static + Send + for<'t> FnOnce(&mut Transaction<'t>) -> Result<T, E>) -> Result<T, E>
+    where
+        T: 'static + Send,
+        E: 'static + Send + From<Error>,
+    {
+        self.run(move |db| db.transaction(func)).await
+    }
+
+    /// Runs the given closure in a transaction, and runs it again in a new transaction if it fails with a transient error.
+    ///
+    /// This runs [`sqlite::Database::transaction_with_retry()`] on the blocking pool, so the backoff between attempts is slept there
+    /// as well.
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`Transaction`] to execute statements on.
+    ///
+    /// # Returns
+    /// The value returned by `func` in the attempt that succeeded.
+    ///
+    /// # Errors
+    /// This function errors if the last attempt fails, or if any attempt fails with an error that is not retried.
+    pub async fn transaction_with_retry<T, E>(
+        &self,
+        func: impl 'static + Send + for<'t> FnMut(&mut Transaction<'t>) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        T: 'static + Send,
+        E: 'static + Send + From<Error> + Display + Retryable,
+    {
+        self.run(move |db| db.transaction_with_retry(func)).await
+    }
+}
+impl From<sqlite::Database> for Database {
+    #[inline]
+    fn from(value: sqlite::Database) -> Self { Self { db: value } }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    25 Dec 2023, 12:25:23
 //  Last edited:
-//    25 Dec 2023, 12:31:10
+//    18 Oct 2026, 14:28:12
 //  Auto updated?
 //    Yes
 //
@@ -18,9 +18,12 @@ use std::fmt::{Display, Formatter, Result as FResult};
 use std::fs::File;
 use std::io::Read as _;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, fs};
 
 use log::debug;
 use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
 
 
 /***** ERRORS *****/
@@ -30,7 +33,13 @@ pub enum Error {
     /// Failed to open a given file.
     FileOpen { path: PathBuf, err: std::io::Error },
     /// Failed to read the given file as a [`ConfigFile`].
-    FileRead { kind: &'static str, path: PathBuf, err: Box<dyn error::Error> },
+    FileRead { kind: &'static str, path: PathBuf, err: Box<dyn Send + Sync + error::Error> },
+    /// A config file references an environment variable that is not set.
+    EnvVarMissing { path: PathBuf, var: String },
+    /// A config file has a `${` that is not closed.
+    EnvVarUnclosed { path: PathBuf },
+    /// The config files layered on the given base file do not make a valid config.
+    LayersInvalid { path: PathBuf, err: serde_json::Error },
     /// Unknown extension for given config file path.
     UnknownExt { path: PathBuf },
 }
@@ -40,7 +49,10 @@ impl Display for Error {
         match self {
             FileOpen { path, .. } => write!(f, "Failed to open file '{}'", path.display()),
             FileRead { kind, path, .. } => write!(f, "Failed to read file '{}' as a {} credentials file", path.display(), kind),
-            UnknownExt { path } => write!(f, "Unknown extension for credentials file '{}' (expected 'json', 'yml' or 'yaml')", path.display()),
+            EnvVarMissing { path, var } => write!(f, "Environment variable '{var}' referenced in file '{}' is not set", path.display()),
+            EnvVarUnclosed { path } => write!(f, "Unclosed '${{' in file '{}'", path.display()),
+            LayersInvalid { path, .. } => write!(f, "Failed to parse the configuration layered on file '{}'", path.display()),
+            UnknownExt { path } => write!(f, "Unknown extension for credentials file '{}' (expected 'json', 'yml', 'yaml' or 'toml')", path.display()),
         }
     }
 }
@@ -50,6 +62,9 @@ impl error::Error for Error {
         match self {
             FileOpen { err, .. } => Some(err),
             FileRead { err, .. } => Some(&**err),
+            EnvVarMissing { .. } => None,
+            EnvVarUnclosed { .. } => None,
+            LayersInvalid { err, .. } => Some(err),
             UnknownExt { .. } => None,
         }
     }
@@ -59,8 +74,396 @@ impl error::Error for Error {
 
 
 
+/***** HELPERS *****/
+/// The formats in which config files can be written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    /// It's JSON.
+    Json,
+    /// It's YAML.
+    Yaml,
+    /// It's TOML.
+    Toml,
+}
+
+/// Reads a config file, substituting any environment variables in it and determining its format.
+///
+/// # Arguments
+/// - `path`: The path of the file to read.
+///
+/// # Returns
+/// A tuple with the (substituted) contents of the file and its [`Format`].
+///
+/// # Errors
+/// This function errors if we failed to read the file, if it references an environment variable that is not set or if its
+/// extension is not recognized.
+fn read_config(path: &Path) -> Result<(String, Format), Error> {
+    // Check the extension before reading anything
+    let format: Option<Format> = match path.extension() {
+        Some(ext) if ext == OsStr::new("json") => Some(Format::Json),
+        Some(ext) if ext == OsStr::new("yml") || ext == OsStr::new("yaml") => Some(Format::Yaml),
+        Some(ext) if ext == OsStr::new("toml") => Some(Format::Toml),
+        Some(_) => return Err(Error::UnknownExt { path: path.into() }),
+        None => None,
+    };
+
+    // Read it in its entirety
+    let mut raw: String = String::new();
+    match File::open(path) {
+        Ok(mut handle) => {
+            if let Err(err) = handle.read_to_string(&mut raw) {
+                return Err(Error::FileRead { kind: "UTF-8", path: path.into(), err: Box::new(err) });
+            }
+        },
+        Err(err) => return Err(Error::FileOpen { path: path.into(), err }),
+    }
+    let raw: String = interpolate(path, &raw)?;
+
+    // Guess the format if the extension didn't tell us
+    let format: Format = match format {
+        Some(format) => format,
+        None => {
+            let trimmed: &str = raw.trim_start();
+            let format: Format = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                Format::Json
+            } else if toml::from_str::<toml::Table>(&raw).is_ok() {
+                Format::Toml
+            } else {
+                Format::Yaml
+            };
+            debug!("Config file '{}' has no extension; guessed it is {:?}", path.display(), format);
+            format
+        },
+    };
+    Ok((raw, format))
+}
+
+/// Substitutes the environment variables referenced in a config file.
+///
+/// See [`load_config_file()`] for the syntax.
+///
+/// # Arguments
+/// - `path`: The path of the file, for errors.
+/// - `raw`: The contents of the file.
+///
+/// # Returns
+/// The given `raw` with all references substituted.
+///
+/// # Errors
+/// This function errors if a referenced variable is not set and has no default, or if a reference is not closed.
+fn interpolate(path: &Path, raw: &str) -> Result<String, Error> {
+    let mut res: String = String::with_capacity(raw.len());
+    let mut rest: &str = raw;
+    while let Some(pos) = rest.find('$') {
+        res.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        // Find out what kind of dollar this is
+        if let Some(after) = rest.strip_prefix("$${") {
+            res.push_str("${");
+            rest = after;
+            continue;
+        }
+        let after: &str = match rest.strip_prefix("${") {
+            Some(after) => after,
+            None => {
+                res.push('$');
+                rest = &rest[1..];
+                continue;
+            },
+        };
+
+        // Find the variable and its default
+        let end: usize = match after.find('}') {
+            Some(end) => end,
+            None => return Err(Error::EnvVarUnclosed { path: path.into() }),
+        };
+        let (var, default): (&str, Option<&str>) = match after[..end].split_once(":-") {
+            Some((var, default)) => (var, Some(default)),
+            None => (&after[..end], None),
+        };
+        match (env::var(var), default) {
+            (Ok(value), None) => res.push_str(&value),
+            (Ok(value), Some(_)) if !value.is_empty() => res.push_str(&value),
+            (_, Some(default)) => res.push_str(default),
+            (Err(_), None) => return Err(Error::EnvVarMissing { path: path.into(), var: var.into() }),
+        }
+        rest = &after[end + 1..];
+    }
+    res.push_str(rest);
+    Ok(res)
+}
+
+/// Parses a config file as a generic JSON value, such that it can be layered with others.
+///
+/// # Arguments
+/// - `path`: The path of the file to parse.
+///
+/// # Returns
+/// The contents of the file as a [`Value`].
+///
+/// # Errors
+/// This function errors if we failed to read the file or if it is not valid (see [`read_config()`]).
+fn load_config_value(path: &Path) -> Result<Value, Error> {
+    let (raw, format): (String, Format) = read_config(path)?;
+    match format {
+        Format::Json => match serde_json::from_str(&raw) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(Error::FileRead { kind: "JSON", path: path.into(), err: Box::new(err) }),
+        },
+        Format::Yaml => match serde_yaml::from_str(&raw) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(Error::FileRead { kind: "YAML", path: path.into(), err: Box::new(err) }),
+        },
+        Format::Toml => match toml::from_str(&raw) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(Error::FileRead { kind: "TOML", path: path.into(), err: Box::new(err) }),
+        },
+    }
+}
+
+/// Layers one config on top of another.
+///
+/// Objects are merged key-by-key, while any other value in `overlay` replaces the one in `base`.
+///
+/// # Arguments
+/// - `base`: The config to layer on top of.
+/// - `overlay`: The config to layer on top of `base`.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value),
+                    None => {
+                        base.insert(key, value);
+                    },
+                }
+            }
+        },
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Sets a value from an environment variable in a config.
+///
+/// The value takes the type of the one it replaces (e.g., `"20"` replaces a number with a number, but a string with a string). Values
+/// that don't replace a number or boolean are always strings, such that, e.g., a password of `123456` or `null` is not mistaken for
+/// something else.
+///
+/// # Arguments
+/// - `node`: The config to set the value in.
+/// - `keys`: The path of keys to the value to set.
+/// - `raw`: The value of the environment variable.
+fn apply_override(node: &mut Value, keys: &[String], raw: &str) {
+    let (key, rest): (&String, &[String]) = match keys.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    if !node.is_object() {
+        *node = Value::Object(Map::new());
+    }
+    if let Value::Object(map) = node {
+        if !rest.is_empty() {
+            apply_override(map.entry(key.clone()).or_insert(Value::Null), rest, raw);
+            return;
+        }
+        let value: Value = match map.get(key) {
+            Some(Value::String(_)) => Value::String(raw.into()),
+            Some(Value::Bool(_)) => match raw.to_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Value::String(raw.into()),
+            },
+            Some(Value::Number(_)) => match raw.parse::<Number>() {
+                Ok(number) => Value::Number(number),
+                Err(_) => Value::String(raw.into()),
+            },
+            _ => Value::String(raw.into()),
+        };
+        map.insert(key.clone(), value);
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes the outcome of executing a statement on a database.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ExecuteResult {
+    /// The number of rows inserted, updated or deleted by the statement.
+    ///
+    /// Only meaningful for data-modifying statements; it is `0` for others (e.g., `CREATE TABLE`).
+    pub rows_affected:  u64,
+    /// The ID generated for an `AUTO_INCREMENT` column by an insert statement, if any.
+    pub last_insert_id: Option<u64>,
+}
+
+/// Describes the health of a database, e.g., to feed a readiness probe.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct HealthReport {
+    /// How long it took to get a connection and run a query on it.
+    pub latency:        Duration,
+    /// The version of the database server (or, for SQLite, of the library).
+    pub server_version: String,
+    /// The state of the connection pool.
+    pub pool:           PoolStats,
+}
+
+/// Describes the connection pool of a database, as part of a [`HealthReport`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PoolStats {
+    /// The minimum number of connections the pool keeps open.
+    pub min_connections:  usize,
+    /// The maximum number of connections the pool opens.
+    pub max_connections:  usize,
+    /// The number of connections that are open but not in use, if the backend keeps track of it.
+    pub idle_connections: Option<usize>,
+}
+
+
+
+
+
+/// Loads a config from multiple layers: a base file, an (optional) environment-specific file and environment variables.
+///
+/// Each file is read like [`load_config_file()`], after which the layers are merged key-by-key. In order, these are:
+/// 1. The base file, e.g., `database.yml`;
+/// 2. The file for the environment given with `Self::environment()`, e.g., `database.production.yml`, if it exists; and
+/// 3. Environment variables starting with the prefix (`DATABASE_` by default), where the rest of the name is the (lowercased) key
+///    to set and `__` separates nested keys. For example, `DATABASE_MAX_CONNECTIONS=20` sets `max_connections`, and
+///    `DATABASE_CREDS__PASSWORD=secret` sets `password` in `creds`. Values take the type of the value they replace, and are strings if
+///    there is none; so to set a number or boolean from the environment, give it a (default) value in one of the files.
+///
+/// # Example
+/// ```no_run
+/// use database::common::ConfigLoader;
+/// # use serde::Deserialize;
+/// # #[derive(Deserialize)]
+/// # struct Config {}
+///
+/// let config: Config = ConfigLoader::new("database.yml").environment("production").load().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigLoader {
+    /// The path to the base file.
+    path: PathBuf,
+    /// The name of the environment whose file to layer on top of the base file, if any.
+    environment: Option<String>,
+    /// The prefix of the environment variables to layer on top of the files, if any.
+    env_prefix: Option<String>,
+}
+impl ConfigLoader {
+    /// Constructor for the ConfigLoader.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the base file.
+    ///
+    /// # Returns
+    /// A new ConfigLoader that layers environment variables prefixed with `DATABASE_` on top of the given file.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self { Self { path: path.into(), environment: None, env_prefix: Some("DATABASE_".into()) } }
+
+    /// Sets the environment whose file to layer on top of the base file.
+    ///
+    /// The file is found next to the base file by inserting the environment before its extension, e.g., `database.production.yml`.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the environment.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn environment(mut self, name: impl Into<String>) -> Self {
+        self.environment = Some(name.into());
+        self
+    }
+
+    /// Sets the prefix of the environment variables to layer on top of the files.
+    ///
+    /// # Arguments
+    /// - `prefix`: The prefix, or [`None`] to not read any environment variables.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn env_prefix(mut self, prefix: Option<impl Into<String>>) -> Self {
+        self.env_prefix = prefix.map(Into::into);
+        self
+    }
+
+    /// Returns the path of the environment-specific file, if an environment is set.
+    fn environment_path(&self) -> Option<PathBuf> {
+        let environment: &str = self.environment.as_deref()?;
+        let stem: &str = self.path.file_stem()?.to_str()?;
+        let name: String = match self.path.extension() {
+            Some(ext) => format!("{stem}.{environment}.{}", ext.to_string_lossy()),
+            None => format!("{stem}.{environment}"),
+        };
+        Some(self.path.with_file_name(name))
+    }
+
+    /// Loads the config from all layers.
+    ///
+    /// # Generics
+    /// - `F`: The type to load to.
+    ///
+    /// # Returns
+    /// A new instance of `F` loaded from the layers.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read any of the files (see [`load_config_file()`]), or if the layers together are not a
+    /// valid `F`.
+    pub fn load<F: DeserializeOwned>(&self) -> Result<F, Error> {
+        // Layer the files
+        debug!("Loading layered config from '{}'...", self.path.display());
+        let mut config: Value = load_config_value(&self.path)?;
+        if let Some(path) = self.environment_path() {
+            if fs::metadata(&path).is_ok() {
+                debug!("Layering environment config file '{}'", path.display());
+                merge(&mut config, load_config_value(&path)?);
+            } else {
+                debug!("No environment config file '{}'; skipping", path.display());
+            }
+        }
+
+        // Layer the environment variables, sorted to be deterministic
+        if let Some(prefix) = &self.env_prefix {
+            let mut vars: Vec<(String, String)> = env::vars().filter(|(name, _)| name.starts_with(prefix.as_str())).collect();
+            vars.sort();
+            for (name, value) in vars {
+                debug!("Layering environment variable '{name}'");
+                let keys: Vec<String> = name[prefix.len()..].split("__").map(str::to_lowercase).collect();
+                apply_override(&mut config, &keys, &value);
+            }
+        }
+
+        // Now parse the result
+        match serde_json::from_value(config) {
+            Ok(config) => Ok(config),
+            Err(err) => Err(Error::LayersInvalid { path: self.path.clone(), err }),
+        }
+    }
+}
+
+
+
+
+
 /***** LIBRARY FUNCTIONS *****/
-/// Loads a [`Deserialize`]able type from the given path, using various backends depending on the given path's extension.
+/// Loads a [`Deserialize`](serde::Deserialize)able type from the given path, using various backends depending on the given path's
+/// extension.
+///
+/// Files ending in `.json`, `.yml`/`.yaml` and `.toml` are read as JSON, YAML and TOML, respectively. If the path has no extension,
+/// the format is guessed from the contents.
+///
+/// Before parsing, any `${VAR}` in the file is replaced with the value of the environment variable `VAR`, and any `${VAR:-default}`
+/// with its value or `default` if it is unset or empty. Write `$${` for a literal `${`. Note that values are substituted as-is, so
+/// quote them if they may contain characters that are special to the file's format.
+///
+/// See [`ConfigLoader`] to layer multiple files and environment variables on top of each other.
 ///
 /// # Generics
 /// - `F`: The type to load to.
@@ -72,47 +475,224 @@ impl error::Error for Error {
 /// A new instance of `F` loaded from disk.
 ///
 /// # Errors
-/// This function may error if an I/O error occurred.
+/// This function may error if an I/O error occurred, if a referenced environment variable is not set or if the file is not valid.
 pub fn load_config_file<F: DeserializeOwned>(path: impl AsRef<Path>) -> Result<F, Error> {
     let path: &Path = path.as_ref();
 
     // Attempt to read the credentials file
     debug!("Loading config file '{}'...", path.display());
-    let config: F = match File::open(path) {
-        Ok(mut handle) => {
-            if path.extension().map(|ext| ext == OsStr::new("json")).unwrap_or(false) {
-                debug!("Config file '{}' is JSON", path.display());
-                match serde_json::from_reader(handle) {
-                    Ok(config) => config,
-                    Err(err) => return Err(Error::FileRead { kind: "JSON", path: path.into(), err: Box::new(err) }),
-                }
-            } else if path.extension().map(|ext| ext == OsStr::new("yml") || ext == OsStr::new("yaml")).unwrap_or(false) {
-                debug!("Config file '{}' is YAML", path.display());
-                match serde_yaml::from_reader(handle) {
-                    Ok(creds) => creds,
-                    Err(err) => return Err(Error::FileRead { kind: "YAML", path: path.into(), err: Box::new(err) }),
-                }
-            } else if path.extension().map(|ext| ext == OsStr::new("toml")).unwrap_or(false) {
-                debug!("Config file '{}' is TOML", path.display());
-
-                // Read it in its entirety first
-                let mut raw: String = String::new();
-                if let Err(err) = handle.read_to_string(&mut raw) {
-                    return Err(Error::FileRead { kind: "UTF-8", path: path.into(), err: Box::new(err) });
-                }
+    let (raw, format): (String, Format) = read_config(path)?;
 
-                // Parse as TOML
-                match toml::from_str(&raw) {
-                    Ok(creds) => creds,
-                    Err(err) => return Err(Error::FileRead { kind: "TOML", path: path.into(), err: Box::new(err) }),
-                }
-            } else {
-                return Err(Error::UnknownExt { path: path.into() });
-            }
+    // Parse it with the appropriate backend
+    let config: F = match format {
+        Format::Json => match serde_json::from_str(&raw) {
+            Ok(config) => config,
+            Err(err) => return Err(Error::FileRead { kind: "JSON", path: path.into(), err: Box::new(err) }),
+        },
+        Format::Yaml => match serde_yaml::from_str(&raw) {
+            Ok(config) => config,
+            Err(err) => return Err(Error::FileRead { kind: "YAML", path: path.into(), err: Box::new(err) }),
+        },
+        Format::Toml => match toml::from_str(&raw) {
+            Ok(config) => config,
+            Err(err) => return Err(Error::FileRead { kind: "TOML", path: path.into(), err: Box::new(err) }),
         },
-        Err(err) => return Err(Error::FileOpen { path: path.into(), err }),
     };
 
     // Dope done
     Ok(config)
 }
+
+/// Redacts the password in the given connection URL, such that it can safely be shown in errors and logs.
+///
+/// This works on the raw text, so it also redacts URLs that fail to parse (e.g., because the password contains a `/`, `?` or `#`). Everything
+/// between the first `:` after the scheme and the last `@` before the query is considered the password.
+///
+/// # Arguments
+/// - `url`: The URL to redact.
+///
+/// # Returns
+/// The given `url`, with any password replaced by `***`.
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub(crate) fn redact_url(url: &str) -> String {
+    // Find the authority of the URL
+    let start: usize = match url.find("://") {
+        Some(pos) => pos + 3,
+        None => return url.into(),
+    };
+
+    // Find the end of the userinfo, which is the last `@` before the query (or the last one at all, in case the password contains a `?`)
+    let query: usize = url[start..].find('?').map(|pos| start + pos).unwrap_or(url.len());
+    let at: usize = match url[start..query].rfind('@').or_else(|| url[start..].rfind('@')) {
+        Some(pos) => start + pos,
+        None => return url.into(),
+    };
+
+    // Find the password in it, if any
+    match url[start..at].find(':') {
+        Some(pos) => format!("{}***{}", &url[..start + pos + 1], &url[at..]),
+        None => url.into(),
+    }
+}
+
+/// Parses a boolean parameter in a connection URL.
+///
+/// # Arguments
+/// - `value`: The value of the parameter, which may be `true`/`false`, `1`/`0`, `yes`/`no` or `on`/`off`.
+///
+/// # Returns
+/// The parsed boolean, or [`None`] if `value` is none of the above.
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub(crate) fn parse_url_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+
+    /// Writes a file with the given name and contents to a fresh temporary directory.
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let dir: PathBuf = env::temp_dir().join(format!("database-common-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path: PathBuf = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn interpolate_vars() {
+        let path: &Path = Path::new("test.yml");
+        env::set_var("DATABASE_TEST_INTERPOLATE_SET", "value");
+        env::set_var("DATABASE_TEST_INTERPOLATE_EMPTY", "");
+        env::remove_var("DATABASE_TEST_INTERPOLATE_UNSET");
+
+        assert_eq!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_SET}").unwrap(), "a: value");
+        assert_eq!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_SET:-default}").unwrap(), "a: value");
+        assert_eq!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_UNSET:-default}").unwrap(), "a: default");
+        assert_eq!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_EMPTY:-default}").unwrap(), "a: default");
+        assert_eq!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_UNSET:-}").unwrap(), "a: ");
+        assert_eq!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_EMPTY}").unwrap(), "a: ");
+
+        // Escapes and lone dollars are kept
+        assert_eq!(interpolate(path, "a: $${DATABASE_TEST_INTERPOLATE_SET}").unwrap(), "a: ${DATABASE_TEST_INTERPOLATE_SET}");
+        assert_eq!(interpolate(path, "a: $5 and $$ and $").unwrap(), "a: $5 and $$ and $");
+
+        // Errors
+        let err: Result<String, Error> = interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_UNSET}");
+        assert!(matches!(err, Err(Error::EnvVarMissing { var, .. }) if var == "DATABASE_TEST_INTERPOLATE_UNSET"));
+        assert!(matches!(interpolate(path, "a: ${DATABASE_TEST_INTERPOLATE_SET"), Err(Error::EnvVarUnclosed { .. })));
+    }
+
+    #[test]
+    fn merge_layers() {
+        let mut base: Value = json!({ "host": "localhost", "port": 3306, "creds": { "user": "app", "password": "a" }, "init": ["SET a = 1"] });
+        merge(&mut base, json!({ "port": 3307, "creds": { "password": "b" }, "init": ["SET b = 2"], "extra": null }));
+        assert_eq!(
+            base,
+            json!({ "host": "localhost", "port": 3307, "creds": { "user": "app", "password": "b" }, "init": ["SET b = 2"], "extra": null })
+        );
+
+        // Non-objects replace objects and vice versa
+        let mut base: Value = json!({ "creds": { "user": "app" } });
+        merge(&mut base, json!({ "creds": "file.yml" }));
+        assert_eq!(base, json!({ "creds": "file.yml" }));
+    }
+
+    #[test]
+    fn apply_override_types() {
+        let mut config: Value = json!({ "port": 3306, "check": true, "name": "app", "creds": { "password": null } });
+        let keys = |path: &str| -> Vec<String> { path.split('.').map(String::from).collect() };
+
+        // Known types are kept where possible
+        apply_override(&mut config, &keys("port"), "3307");
+        apply_override(&mut config, &keys("check"), "FALSE");
+        apply_override(&mut config, &keys("name"), "42");
+        assert_eq!(config["port"], json!(3307));
+        assert_eq!(config["check"], json!(false));
+        assert_eq!(config["name"], json!("42"));
+        apply_override(&mut config, &keys("port"), "many");
+        assert_eq!(config["port"], json!("many"));
+
+        // Anything else is a string, even if it looks like something else
+        for raw in ["123456", "null", "true", "[1]", "{}"] {
+            apply_override(&mut config, &keys("creds.password"), raw);
+            assert_eq!(config["creds"]["password"], json!(raw));
+            apply_override(&mut config, &keys("new.nested.key"), raw);
+            assert_eq!(config["new"]["nested"]["key"], json!(raw));
+        }
+
+        // Scalars are replaced by objects to set nested keys
+        apply_override(&mut config, &keys("name.first"), "x");
+        assert_eq!(config["name"], json!({ "first": "x" }));
+    }
+
+    #[test]
+    fn loader_layers_env() {
+        #[derive(serde::Deserialize)]
+        struct Creds {
+            user:     String,
+            password: Option<String>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Config {
+            port:  u16,
+            creds: Creds,
+        }
+
+        let path: PathBuf = write_temp("loader.yml", "port: 3306\ncreds:\n  user: app\n");
+        env::set_var("DATABASE_TEST_LOADER_PORT", "3307");
+        env::set_var("DATABASE_TEST_LOADER_CREDS__PASSWORD", "123456");
+        let config: Config = ConfigLoader::new(&path).env_prefix(Some("DATABASE_TEST_LOADER_")).load().unwrap();
+        assert_eq!(config.port, 3307);
+        assert_eq!(config.creds.user, "app");
+        assert_eq!(config.creds.password.as_deref(), Some("123456"));
+    }
+
+    #[test]
+    fn read_config_sniffs_format() {
+        for (name, contents, format) in [
+            ("sniff-json", "  {\"host\": \"localhost\"}", Format::Json),
+            ("sniff-toml", "host = \"localhost\"\nport = 3306\n", Format::Toml),
+            ("sniff-yaml", "host: localhost\nport: 3306\n", Format::Yaml),
+            ("sniff.yml", "{\"host\": \"localhost\"}", Format::Yaml),
+        ] {
+            let path: PathBuf = write_temp(name, contents);
+            assert_eq!(read_config(&path).unwrap().1, format, "{name}");
+            assert_eq!(load_config_value(&path).unwrap()["host"], json!("localhost"), "{name}");
+        }
+        assert!(matches!(read_config(&write_temp("sniff.ini", "host = localhost")), Err(Error::UnknownExt { .. })));
+    }
+
+    #[test]
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    fn redact_url_passwords() {
+        assert_eq!(redact_url("mysql://app:secret@db:3306/app?ssl_mode=required"), "mysql://app:***@db:3306/app?ssl_mode=required");
+        assert_eq!(redact_url("mysql://app:s3cr/t@db/app"), "mysql://app:***@db/app");
+        assert_eq!(redact_url("mysql://app:p#ss@db/app"), "mysql://app:***@db/app");
+        assert_eq!(redact_url("mysql://app:p?ss@db/app"), "mysql://app:***@db/app");
+        assert_eq!(redact_url("mysql://app:p@ss@db/app"), "mysql://app:***@db/app");
+        assert_eq!(redact_url("mysql://app:p@ss@db/app?user=a@b"), "mysql://app:***@db/app?user=a@b");
+    }
+
+    #[test]
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    fn redact_url_without_password() {
+        assert_eq!(redact_url("mysql://app@db:3306/app"), "mysql://app@db:3306/app");
+        assert_eq!(redact_url("mysql://db:3306/app"), "mysql://db:3306/app");
+        assert_eq!(redact_url("sqlite:///var/lib/app.db?mode=ro"), "sqlite:///var/lib/app.db?mode=ro");
+        assert_eq!(redact_url("not a url"), "not a url");
+    }
+}
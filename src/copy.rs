@@ -0,0 +1,401 @@
+//  COPY.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:26:57
+//  Last edited:
+//    18 Oct 2026, 14:29:40
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements copying tables between databases, possibly of different
+//!   backends (e.g., to migrate from SQLite to MySQL).
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use log::{debug, info};
+
+use crate::spec::{cast_to_columns, Database, Executor as _};
+use crate::sql::{
+    Condition, Dialect, Ident, Operator, Order, Row, Statement, StatementCreateIndex, StatementCreateTable, StatementInsert, StatementSelect,
+    Type, Value,
+};
+
+
+/***** CONSTANTS *****/
+/// The length of the `VARCHAR`s that text columns used in keys or indices are created as in MySQL, which cannot index `TEXT`.
+const MYSQL_KEY_LENGTH: usize = 255;
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from copying tables.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read the definition or rows of the table from the source database.
+    ReadFailed { table: Ident, err: Box<dyn Send + Sync + error::Error> },
+    /// Failed to create the table on the destination database.
+    CreateFailed { table: Ident, err: Box<dyn Send + Sync + error::Error> },
+    /// Failed to insert rows into the destination database.
+    WriteFailed { table: Ident, err: Box<dyn Send + Sync + error::Error> },
+    /// The table has no primary key to order the rows by.
+    NoPrimaryKey { table: Ident },
+    /// A column used in a key or index has a type that the destination database cannot index.
+    KeyType { table: Ident, col: Ident, ty: Type },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            ReadFailed { table, .. } => write!(f, "Failed to read table '{table}' from source database"),
+            CreateFailed { table, .. } => write!(f, "Failed to create table '{table}' on destination database"),
+            WriteFailed { table, .. } => write!(f, "Failed to insert rows into table '{table}' on destination database"),
+            NoPrimaryKey { table } => write!(f, "Cannot copy table '{table}' without a primary key"),
+            KeyType { table, col, ty } => {
+                write!(f, "Cannot copy column '{col}' of table '{table}', as it is used in a key or index but has unindexable type {ty}")
+            },
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            ReadFailed { err, .. } => Some(&**err),
+            CreateFailed { err, .. } => Some(&**err),
+            WriteFailed { err, .. } => Some(&**err),
+            NoPrimaryKey { .. } => None,
+            KeyType { .. } => None,
+        }
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Adapts the definition of a table such that it can be created in the given dialect.
+///
+/// MySQL cannot use `TEXT` or `BLOB` columns in keys or indices, which is what SQLite reports for most textual types (e.g., UUIDs and
+/// decimals). Such text columns are created as `VARCHAR`s of at most [`MYSQL_KEY_LENGTH`] characters instead; rows with longer values
+/// then fail to be inserted.
+///
+/// # Arguments
+/// - `def`: The definition of the table to adapt.
+/// - `indexes`: The indices that will be created on the table.
+/// - `dialect`: The [`Dialect`] of the database the table will be created in.
+///
+/// # Errors
+/// This function errors if a column used in a key or index has a type that cannot be indexed in `dialect` and cannot be adapted.
+fn adapt_keys(def: &mut StatementCreateTable, indexes: &[StatementCreateIndex], dialect: Dialect) -> Result<(), Error> {
+    if dialect != Dialect::MySql {
+        return Ok(());
+    }
+    for col in &mut def.cols {
+        let is_key: bool = def.primary_key.as_ref() == Some(&col.name)
+            || def.unique.contains(&col.name)
+            || indexes.iter().any(|index| index.cols.contains(&col.name))
+            || def.foreign_keys.iter().any(|fk| fk.cols.contains(&col.name));
+        if !is_key {
+            continue;
+        }
+        match col.ty {
+            Type::Text => col.ty = Type::VarChar(MYSQL_KEY_LENGTH),
+            Type::Clob(len) => col.ty = Type::VarChar(len.min(MYSQL_KEY_LENGTH)),
+            Type::Blob(_) | Type::Json => return Err(Error::KeyType { table: def.name.clone(), col: col.name.clone(), ty: col.ty.clone() }),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes how far a [`CopyTable`] has progressed.
+#[derive(Clone, Debug, Default)]
+pub struct CopyProgress {
+    /// The number of rows copied (by this run).
+    pub rows_copied: u64,
+    /// The primary key of the last row copied, if any. Pass this to [`CopyTable::after()`] to resume an interrupted copy.
+    pub cursor:      Option<Value>,
+}
+
+
+
+/// Copies a table from one database to another, e.g., from SQLite to MySQL.
+///
+/// The definition of the table is read from the source database (see [`Executor::describe_table()`](crate::spec::Executor)) and, if the
+/// table does not yet exist, created on the destination with its indices. The rows are then copied in batches in the order of the
+/// primary key, where each batch is inserted in its own transaction. After every batch, the key of the last row copied is reported as a
+/// cursor, which can be used to resume the copy if it is interrupted.
+///
+/// Note that the destination table is created with the foreign keys of the source, so tables referred to must be copied first. Text
+/// columns used in keys or indices are created as `VARCHAR(255)` in MySQL, which cannot index `TEXT`.
+///
+/// # Example
+/// ```rust,ignore
+/// use database::copy::CopyTable;
+///
+/// let progress = CopyTable::new("users").batch_size(500).run(&sqlite, &mysql, |progress| {
+///     println!("Copied {} rows", progress.rows_copied);
+/// })?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct CopyTable {
+    /// The name of the table to copy.
+    pub table:      Ident,
+    /// The maximum number of rows to copy per batch.
+    pub batch_size: usize,
+    /// If given, only rows with a primary key greater than this are copied.
+    pub after:      Option<Value>,
+    /// Whether to create the table on the destination if it does not exist.
+    pub create:     bool,
+}
+impl CopyTable {
+    /// Constructor for the CopyTable.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to copy.
+    ///
+    /// # Returns
+    /// A new CopyTable that copies all rows in batches of 1000, creating the table on the destination if necessary.
+    #[inline]
+    pub fn new(table: impl Into<Ident>) -> Self { Self { table: table.into(), batch_size: 1000, after: None, create: true } }
+
+    /// Changes the number of rows copied per batch.
+    ///
+    /// # Arguments
+    /// - `batch_size`: The maximum number of rows to copy per batch. This is lowered for wide tables if needed, such that a batch never
+    ///   has more values than the destination supports in one statement (see [`Dialect::max_params()`]).
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Resumes the copy after a given row.
+    ///
+    /// # Arguments
+    /// - `after`: If [`Some`], only rows with a primary key greater than this are copied. This is typically the
+    ///   [`CopyProgress::cursor`] of an earlier run.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn after(mut self, after: Option<Value>) -> Self {
+        self.after = after;
+        self
+    }
+
+    /// Changes whether the table is created on the destination if it does not exist.
+    ///
+    /// # Arguments
+    /// - `create`: Whether to enable this option or not.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn create(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+
+    /// Runs the copy.
+    ///
+    /// # Arguments
+    /// - `src`: The [`Database`] to copy the table from.
+    /// - `dst`: The [`Database`] to copy the table to.
+    /// - `progress`: A closure that is called with the [`CopyProgress`] after every batch.
+    ///
+    /// # Returns
+    /// The final [`CopyProgress`].
+    ///
+    /// # Errors
+    /// This function errors if the table has no (single-column) primary key, if it has a key column that `dst` cannot index, or if we
+    /// failed to read it from `src` or to create or insert it in `dst`. In the latter case, the batches copied until then are kept, and the last reported cursor can be used to
+    /// resume.
+    pub fn run<S: Database, D: Database>(&self, src: &S, dst: &D, mut progress: impl FnMut(&CopyProgress)) -> Result<CopyProgress, Error> {
+        let table: &Ident = &self.table;
+        info!("Copying table '{table}'");
+
+        // Read the table's definition
+        let res: Result<(StatementCreateTable, Vec<StatementCreateIndex>), S::Error> =
+            src.transaction(|tx| Ok((tx.describe_table(table)?, tx.indexes(table)?)));
+        let (mut def, indexes): (StatementCreateTable, Vec<StatementCreateIndex>) = match res {
+            Ok(res) => res,
+            Err(err) => return Err(Error::ReadFailed { table: table.clone(), err: Box::new(err) }),
+        };
+        let key: usize = match def.primary_key.as_ref().and_then(|key| def.cols.iter().position(|c| &c.name == key)) {
+            Some(key) => key,
+            None => return Err(Error::NoPrimaryKey { table: table.clone() }),
+        };
+
+        // Create it on the destination if it doesn't exist yet
+        if self.create {
+            adapt_keys(&mut def, &indexes, D::DIALECT)?;
+            let res: Result<(), D::Error> = dst.transaction(|tx| {
+                if tx.tables()?.contains(table) {
+                    return Ok(());
+                }
+                debug!("Creating table '{table}' on destination database");
+                tx.execute(&Statement::CreateTable(def.clone()))?;
+                for index in &indexes {
+                    tx.execute(&Statement::CreateIndex(index.clone()))?;
+                }
+                Ok(())
+            });
+            if let Err(err) = res {
+                return Err(Error::CreateFailed { table: table.clone(), err: Box::new(err) });
+            }
+        }
+
+        // Copy the rows in batches, using the primary key as cursor, with at most as many values per batch as can be bound
+        let batch_size: usize = self.batch_size.min(D::DIALECT.max_params() / def.cols.len()).max(1);
+        if batch_size < self.batch_size {
+            debug!("Lowered batch size of table '{table}' to {batch_size} rows to fit {} columns per row", def.cols.len());
+        }
+        let mut state: CopyProgress = CopyProgress { rows_copied: 0, cursor: self.after.clone() };
+        loop {
+            let mut select: StatementSelect = StatementSelect::new(table)
+                .cols(def.cols.iter().map(|c| &c.name))
+                .order_by(&def.cols[key].name, Order::Ascending)
+                .limit(Some(batch_size as u64));
+            if let Some(cursor) = &state.cursor {
+                select = select.filter(Some(Condition::Compare { col: def.cols[key].name.clone(), op: Operator::Greater, value: cursor.clone() }));
+            }
+            let rows: Vec<Row> = match src.transaction(|tx| tx.query(&Statement::Select(select))) {
+                Ok(rows) => rows,
+                Err(err) => return Err(Error::ReadFailed { table: table.clone(), err: Box::new(err) }),
+            };
+            if rows.is_empty() {
+                break;
+            }
+
+            let n_rows: usize = rows.len();
+            let values: Vec<Vec<Value>> = rows.into_iter().map(|row| cast_to_columns(row.values, &def.cols)).collect();
+            let cursor: Option<Value> = values.last().map(|row| row[key].clone());
+
+            // Insert the batch atomically, such that the cursor is exact
+            let insert: StatementInsert = StatementInsert { rows: values, ..StatementInsert::new(table, def.cols.iter().map(|c| &c.name)) };
+            if let Err(err) = dst.transaction(|tx| tx.execute_prepared(&Statement::Insert(insert))) {
+                return Err(Error::WriteFailed { table: table.clone(), err: Box::new(err) });
+            }
+            state.rows_copied += n_rows as u64;
+            state.cursor = cursor;
+            debug!("Copied {} rows of table '{table}'", state.rows_copied);
+            progress(&state);
+
+            if n_rows < batch_size {
+                break;
+            }
+        }
+
+        info!("Copied {} rows of table '{table}'", state.rows_copied);
+        Ok(state)
+    }
+}
+
+
+
+/// Copies a table from one database to another, e.g., from SQLite to MySQL.
+///
+/// This is a shorthand for [`CopyTable::new(table).run(src, dst, |_| {})`](CopyTable::run()); use [`CopyTable`] to configure the batch
+/// size, report progress or resume an interrupted copy.
+///
+/// # Arguments
+/// - `src`: The [`Database`] to copy the table from.
+/// - `dst`: The [`Database`] to copy the table to.
+/// - `table`: The name of the table to copy.
+///
+/// # Returns
+/// The number of rows copied.
+///
+/// # Errors
+/// This function errors if the table has no primary key, or if we failed to read it from `src` or to create or insert it in `dst`.
+#[inline]
+pub fn copy_table<S: Database, D: Database>(src: &S, dst: &D, table: impl Into<Ident>) -> Result<u64, Error> {
+    CopyTable::new(table).run(src, dst, |_| {}).map(|progress| progress.rows_copied)
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::ColumnDef;
+
+    /// Returns a table as SQLite would describe it, with text columns as primary key, unique column and index, and one that is not.
+    fn table() -> (StatementCreateTable, Vec<StatementCreateIndex>) {
+        let def: StatementCreateTable = StatementCreateTable::new("users")
+            .add_col(ColumnDef::new("id", Type::Text))
+            .add_col(ColumnDef::new("email", Type::Text))
+            .add_col(ColumnDef::new("name", Type::Clob(100)))
+            .add_col(ColumnDef::new("bio", Type::Text))
+            .primary_key(Some("id".into()))
+            .unique("email");
+        (def, vec![StatementCreateIndex::new("users_name", "users", ["name"])])
+    }
+
+    #[test]
+    fn adapt_keys_mysql() {
+        let (mut def, indexes) = table();
+        adapt_keys(&mut def, &indexes, Dialect::MySql).unwrap();
+        let types: Vec<Type> = def.cols.into_iter().map(|c| c.ty).collect();
+        assert_eq!(types, [Type::VarChar(MYSQL_KEY_LENGTH), Type::VarChar(MYSQL_KEY_LENGTH), Type::VarChar(100), Type::Text]);
+
+        // Blobs cannot be adapted
+        let (def, indexes) = table();
+        let mut def: StatementCreateTable = def.add_col(ColumnDef::new("avatar", Type::Blob(1024))).unique("avatar");
+        assert!(matches!(adapt_keys(&mut def, &indexes, Dialect::MySql), Err(Error::KeyType { col, .. }) if col == "avatar"));
+    }
+
+    #[test]
+    fn adapt_keys_sqlite() {
+        let (mut def, indexes) = table();
+        adapt_keys(&mut def, &indexes, Dialect::Sqlite).unwrap();
+        assert!(def.cols.iter().all(|c| matches!(c.ty, Type::Text | Type::Clob(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn copy_wide_table() {
+        use crate::sqlite;
+
+        // A table with so many columns that the default batch size would need more placeholders than SQLite supports
+        let mut def: StatementCreateTable =
+            StatementCreateTable::new("wide").add_col(ColumnDef::new("id", Type::BigInt)).primary_key(Some("id".into()));
+        for i in 0..69 {
+            def = def.add_col(ColumnDef::new(format!("col{i}"), Type::BigInt));
+        }
+        let rows: Vec<Vec<Value>> = (0..1000).map(|id| (0..70).map(|i| Value::BigInt(id * 100 + i)).collect()).collect();
+        let src: sqlite::Database = sqlite::Database::in_memory(|db| {
+            db.execute(Statement::CreateTable(def.clone()))?;
+            let insert: StatementInsert = StatementInsert { rows, ..StatementInsert::new("wide", def.cols.iter().map(|c| &c.name)) };
+            db.execute(Statement::Insert(insert)).map(|_| ())
+        })
+        .unwrap();
+        let dst: sqlite::Database = sqlite::Database::in_memory(|_| Ok(())).unwrap();
+
+        let mut batches: usize = 0;
+        let progress: CopyProgress = CopyTable::new("wide").run(&src, &dst, |_| batches += 1).unwrap();
+        assert_eq!(progress.rows_copied, 1000);
+        assert!(matches!(progress.cursor, Some(Value::BigInt(99900))), "{:?}", progress.cursor);
+        assert_eq!(batches, 1000usize.div_ceil(Dialect::Sqlite.max_params() / 70));
+        assert_eq!(dst.query(Statement::Select(StatementSelect::new("wide"))).unwrap().len(), 1000);
+    }
+}
@@ -0,0 +1,403 @@
+//  CSV IO.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:20:04
+//  Last edited:
+//    18 Oct 2026, 14:30:38
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements importing tables from and exporting query results to
+//!   CSV files.
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::io::{Read, Write};
+
+use chrono::Utc;
+use log::{debug, info};
+
+use crate::spec::Executor;
+use crate::sql::errors::ValueCastError;
+use crate::sql::{
+    ColumnDef, Ident, Statement, StatementCreateTable, StatementInsert, Value, DATETIME_FORMAT, DATETIME_TZ_FORMAT, DATE_FORMAT, TIME_FORMAT,
+};
+
+
+/***** ERRORS *****/
+/// Defines errors originating from importing or exporting CSV files.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read a record from the CSV file.
+    CsvRead { err: csv::Error },
+    /// Failed to write a record to the CSV file.
+    CsvWrite { err: csv::Error },
+    /// Failed to flush the CSV file.
+    Flush { err: std::io::Error },
+    /// The CSV file has a column that is not in the table it is imported into.
+    UnknownColumn { table: Ident, col: Ident },
+    /// Failed to run the query or insert the rows.
+    ExecuteFailed { err: Box<dyn Send + Sync + error::Error> },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            CsvRead { .. } => write!(f, "Failed to read CSV record"),
+            CsvWrite { .. } => write!(f, "Failed to write CSV record"),
+            Flush { .. } => write!(f, "Failed to flush CSV writer"),
+            UnknownColumn { table, col } => write!(f, "CSV column '{col}' is not defined in table '{table}'"),
+            ExecuteFailed { .. } => write!(f, "Failed to execute statement on the database"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            CsvRead { err } => Some(err),
+            CsvWrite { err } => Some(err),
+            Flush { err } => Some(err),
+            UnknownColumn { .. } => None,
+            ExecuteFailed { err } => Some(&**err),
+        }
+    }
+}
+
+/// Separates errors of the backend from our own while streaming rows.
+enum Failure<E> {
+    /// The backend failed.
+    Backend(E),
+    /// We failed.
+    Csv(Error),
+}
+impl<E> From<E> for Failure<E> {
+    #[inline]
+    fn from(value: E) -> Self { Self::Backend(value) }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Converts a [`Value`] to a CSV field.
+///
+/// # Arguments
+/// - `value`: The [`Value`] to convert.
+///
+/// # Returns
+/// The value as a string that parses back to it using [`Value::cast()`]. NULL is written as an empty field (so empty strings and blobs
+/// are read back as NULL), and `CURRENT_TIMESTAMP` as the current time (in UTC).
+fn to_field(value: &Value) -> String {
+    match value {
+        Value::Boolean(b) => b.to_string(),
+        Value::BigInt(i) => i.to_string(),
+        Value::BigIntUnsigned(i) => i.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::IntUnsigned(i) => i.to_string(),
+        Value::SmallInt(i) => i.to_string(),
+        Value::SmallIntUnsigned(i) => i.to_string(),
+        Value::TinyInt(i) => i.to_string(),
+        Value::TinyIntUnsigned(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Double(d) => d.to_string(),
+        Value::Decimal(d) => d.to_string(),
+
+        Value::String(s) => s.clone(),
+
+        // Written as a time, as importing the literal would insert the time of the import instead (or fail for non-datetime columns)
+        Value::CurrentTimestamp => Utc::now().naive_utc().format(DATETIME_FORMAT).to_string(),
+        Value::Date(d) => d.format(DATE_FORMAT).to_string(),
+        Value::Time(t) => t.format(TIME_FORMAT).to_string(),
+        Value::DateTime(dt) => dt.format(DATETIME_FORMAT).to_string(),
+        Value::DateTimeTz(dt) => dt.format(DATETIME_TZ_FORMAT).to_string(),
+
+        Value::Blob(b) => b.iter().map(|byte| format!("{byte:02X}")).collect(),
+        Value::Clob(c) => c.clone(),
+        Value::Json(j) => j.to_string(),
+        Value::Uuid(u) => u.hyphenated().to_string(),
+        Value::Null => String::new(),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes a field that could not be imported by [`import_csv()`].
+#[derive(Debug)]
+pub struct FieldError {
+    /// The number of the record in the CSV file, where `1` is the first record after the header.
+    pub record: u64,
+    /// The column of the field.
+    pub col:    Ident,
+    /// Why the field's value did not fit its column.
+    pub err:    ValueCastError,
+}
+
+/// Describes the outcome of [`import_csv()`].
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// The number of rows inserted into the table.
+    pub rows_imported: u64,
+    /// The number of records that were skipped because any of their fields could not be imported.
+    pub rows_skipped:  u64,
+    /// The reasons why records were skipped.
+    pub errors:        Vec<FieldError>,
+}
+
+
+
+/// Writes the rows resulting from the given [`Statement`] to a CSV file.
+///
+/// The rows are written as they are read from the database, preceded by a header with the names of the columns. Values are written in
+/// the same formats that [`import_csv()`] reads, where NULL is written as an empty field.
+///
+/// # Arguments
+/// - `db`: The [`Executor`] (e.g., a database or a transaction) to run the statement on.
+/// - `stmt`: The [`Statement`] (e.g., a [`StatementSelect`](crate::sql::StatementSelect)) of which to write the resulting rows.
+/// - `writer`: The [`Write`]r to write the CSV file to.
+///
+/// # Returns
+/// The number of rows written. If this is `0`, only the header is written, with the columns named in the statement (those selected or
+/// returned, or all of the table's for a `SELECT *`).
+///
+/// # Errors
+/// This function errors if we failed to run the statement or to write the CSV file.
+pub fn export_csv<X: Executor>(db: &mut X, stmt: &Statement, writer: impl Write) -> Result<u64, Error> {
+    let mut writer: csv::Writer<_> = csv::Writer::from_writer(writer);
+
+    // Write the rows as they come in
+    let mut n_rows: u64 = 0;
+    let res: Result<(), Failure<X::Error>> = db.for_each_row(stmt, |row| {
+        if n_rows == 0 {
+            if let Err(err) = writer.write_record(row.cols.iter()) {
+                return Err(Failure::Csv(Error::CsvWrite { err }));
+            }
+        }
+        if let Err(err) = writer.write_record(row.values.iter().map(to_field)) {
+            return Err(Failure::Csv(Error::CsvWrite { err }));
+        }
+        n_rows += 1;
+        Ok(())
+    });
+    match res {
+        Ok(()) => {},
+        Err(Failure::Backend(err)) => return Err(Error::ExecuteFailed { err: Box::new(err) }),
+        Err(Failure::Csv(err)) => return Err(err),
+    }
+
+    // Without any rows, we don't know the columns from the database, so take them from the statement instead
+    if n_rows == 0 {
+        let cols: Vec<Ident> = match stmt {
+            Statement::Select(select) if select.cols.is_empty() => match db.describe_table(&select.table) {
+                Ok(def) => def.cols.into_iter().map(|c| c.name).collect(),
+                Err(err) => return Err(Error::ExecuteFailed { err: Box::new(err) }),
+            },
+            Statement::Select(select) => select.cols.clone(),
+            Statement::Insert(insert) => insert.returning.clone(),
+            Statement::Update(update) => update.returning.clone(),
+            Statement::Delete(delete) => delete.returning.clone(),
+            Statement::CreateTable(_) | Statement::CreateIndex(_) | Statement::UseDatabase(_) => vec![],
+        };
+        if !cols.is_empty() {
+            if let Err(err) = writer.write_record(cols.iter().map(Ident::as_str)) {
+                return Err(Error::CsvWrite { err });
+            }
+        }
+    }
+    if let Err(err) = writer.flush() {
+        return Err(Error::Flush { err });
+    }
+
+    debug!("Exported {n_rows} rows to CSV");
+    Ok(n_rows)
+}
+
+/// Inserts the records of a CSV file into a table.
+///
+/// The first record is expected to be a header with the names of the columns, which are matched to the table's [`ColumnDef`]s. The
+/// fields are then cast to the types of their columns (see [`Value::cast()`]), where empty fields are read as NULL. Records with fields
+/// that cannot be cast are skipped and reported; the others are inserted in batches of `batch_size` rows.
+///
+/// Note that the rows are not inserted in a transaction; pass a transaction as `db` to insert all or nothing.
+///
+/// # Arguments
+/// - `db`: The [`Executor`] (e.g., a database or a transaction) to insert the rows with.
+/// - `table`: The definition of the table to insert the rows into.
+/// - `reader`: The [`Read`]er to read the CSV file from.
+/// - `batch_size`: The maximum number of rows to insert per statement. This is lowered for wide tables if needed, such that a statement
+///   never has more values than `db` supports (see [`Dialect::max_params()`](crate::sql::Dialect::max_params())).
+///
+/// # Returns
+/// An [`ImportReport`] with the number of rows inserted and the fields that could not be imported.
+///
+/// # Errors
+/// This function errors if we failed to read the CSV file, if it has columns that are not in `table`, or if we failed to insert rows.
+/// In the latter case, the batches inserted until then are not undone.
+pub fn import_csv<X: Executor>(db: &mut X, table: &StatementCreateTable, reader: impl Read, batch_size: usize) -> Result<ImportReport, Error> {
+    let mut reader: csv::Reader<_> = csv::Reader::from_reader(reader);
+    info!("Importing CSV records into table '{}'", table.name);
+
+    // Match the header to the columns
+    let cols: Vec<&ColumnDef> = match reader.headers() {
+        Ok(header) => {
+            let mut cols: Vec<&ColumnDef> = Vec::with_capacity(header.len());
+            for col in header {
+                match table.cols.iter().find(|c| c.name == col) {
+                    Some(def) => cols.push(def),
+                    None => return Err(Error::UnknownColumn { table: table.name.clone(), col: col.into() }),
+                }
+            }
+            cols
+        },
+        Err(err) => return Err(Error::CsvRead { err }),
+    };
+    let names: Vec<&Ident> = cols.iter().map(|c| &c.name).collect();
+
+    // Read the records, inserting them whenever we have a full batch of at most as many values as can be bound
+    let batch_size: usize = batch_size.min(X::DIALECT.max_params() / names.len().max(1)).max(1);
+    let mut report: ImportReport = ImportReport::default();
+    let mut batch: Vec<Vec<Value>> = Vec::with_capacity(batch_size.min(1024));
+    for (i, record) in reader.records().enumerate() {
+        let record: csv::StringRecord = match record {
+            Ok(record) => record,
+            Err(err) => return Err(Error::CsvRead { err }),
+        };
+
+        // Parse the fields
+        let mut values: Vec<Value> = Vec::with_capacity(cols.len());
+        let mut valid: bool = true;
+        for (field, def) in record.iter().zip(&cols) {
+            let value: Value = if field.is_empty() { Value::Null } else { Value::String(field.into()) };
+            match value.cast(&def.ty) {
+                Ok(value) => values.push(value),
+                Err(err) => {
+                    report.errors.push(FieldError { record: i as u64 + 1, col: def.name.clone(), err });
+                    valid = false;
+                },
+            }
+        }
+        if !valid {
+            report.rows_skipped += 1;
+            continue;
+        }
+        batch.push(values);
+
+        // Insert the batch if it's full
+        if batch.len() >= batch_size {
+            report.rows_imported += insert_batch(db, &table.name, &names, std::mem::take(&mut batch))?;
+        }
+    }
+    if !batch.is_empty() {
+        report.rows_imported += insert_batch(db, &table.name, &names, batch)?;
+    }
+
+    debug!("Imported {} rows into table '{}' ({} skipped)", report.rows_imported, table.name, report.rows_skipped);
+    Ok(report)
+}
+
+/// Inserts a batch of rows into a table.
+///
+/// # Arguments
+/// - `db`: The [`Executor`] to insert the rows with.
+/// - `table`: The name of the table to insert into.
+/// - `cols`: The columns of the rows.
+/// - `rows`: The rows to insert, each with a value for every column in `cols`.
+///
+/// # Returns
+/// The number of rows inserted.
+///
+/// # Errors
+/// This function errors if we failed to insert the rows.
+fn insert_batch<X: Executor>(db: &mut X, table: &Ident, cols: &[&Ident], rows: Vec<Vec<Value>>) -> Result<u64, Error> {
+    let stmt: Statement = Statement::Insert(StatementInsert { rows, ..StatementInsert::new(table, cols.iter().copied()) });
+    match db.execute_prepared(&stmt) {
+        Ok(res) => Ok(res.rows_affected),
+        Err(err) => Err(Error::ExecuteFailed { err: Box::new(err) }),
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone as _};
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    use super::*;
+
+
+    #[test]
+    fn field_round_trip() {
+        let date: NaiveDate = NaiveDate::from_ymd_opt(2023, 12, 30).unwrap();
+        for value in [
+            Value::Boolean(true),
+            Value::BigInt(-42),
+            Value::BigIntUnsigned(u64::MAX),
+            Value::Double(-0.125),
+            Value::Decimal(Decimal::new(-12345, 2)),
+            Value::String("it's, a \"string\"\n".into()),
+            Value::Date(date),
+            Value::Time(NaiveTime::from_hms_micro_opt(12, 13, 1, 500).unwrap()),
+            Value::DateTime(date.and_hms_micro_opt(12, 13, 1, 123456).unwrap()),
+            Value::DateTimeTz(FixedOffset::west_opt(5 * 3600 + 1800).unwrap().with_ymd_and_hms(2023, 12, 30, 12, 13, 1).unwrap()),
+            Value::DateTimeTz(FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2023, 12, 30, 12, 13, 1).unwrap()),
+            Value::Blob(vec![0xDE, 0xAD, 0x00, 0x0F]),
+            Value::Json(serde_json::json!({ "a": [1, "two", null] })),
+            Value::Uuid(Uuid::from_u128(0x67E5_5044_10B1_426F_9247_BB68_0E5F_E0C8)),
+            Value::Null,
+        ] {
+            // Read the field back like `import_csv()` does
+            let field: String = to_field(&value);
+            let read: Value = if field.is_empty() { Value::Null } else { Value::String(field.clone()) };
+            let back: Value = read.cast(&value.ty()).unwrap_or_else(|err| panic!("Failed to cast {value:?} back from {field:?}: {err}"));
+            assert_eq!(format!("{back:?}"), format!("{value:?}"), "{value:?} did not round-trip via {field:?}");
+        }
+
+        // The current time is written as a datetime
+        assert!(Value::String(to_field(&Value::CurrentTimestamp)).cast(&crate::sql::Type::DateTime(0)).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn import_reports_fields() {
+        use crate::sql::{Condition, StatementSelect, Type};
+        use crate::sqlite;
+
+        let table: StatementCreateTable = StatementCreateTable::new("users")
+            .add_col(ColumnDef::new("id", Type::BigInt))
+            .add_col(ColumnDef::new("age", Type::TinyIntUnsigned))
+            .add_col(ColumnDef::new("born", Type::Date))
+            .primary_key(Some("id".into()));
+        let mut db: sqlite::Database = sqlite::Database::in_memory(|db| db.execute(Statement::CreateTable(table.clone())).map(|_| ())).unwrap();
+
+        let csv: &str = "id,born,age\n1,2000-01-01,24\n2,yesterday,24\n3,,300\n4,2001-02-03,\n5,soon,-1\n";
+        let report: ImportReport = import_csv(&mut db, &table, csv.as_bytes(), 2).unwrap();
+        assert_eq!((report.rows_imported, report.rows_skipped), (2, 3));
+        let errors: Vec<(u64, &str)> = report.errors.iter().map(|e| (e.record, e.col.as_str())).collect();
+        assert_eq!(errors, [(2, "born"), (3, "age"), (5, "born"), (5, "age")]);
+
+        // Unknown columns are rejected before anything is inserted
+        assert!(matches!(import_csv(&mut db, &table, "id,name\n6,bob\n".as_bytes(), 2), Err(Error::UnknownColumn { col, .. }) if col == "name"));
+
+        // The imported rows export the same, and empty results still get a header
+        let mut out: Vec<u8> = vec![];
+        let select: Statement = Statement::Select(StatementSelect::new("users").cols(["id", "age", "born"]));
+        assert_eq!(export_csv(&mut db, &select, &mut out).unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "id,age,born\n1,24,2000-01-01\n4,,2001-02-03\n");
+        let mut out: Vec<u8> = vec![];
+        let select: Statement = Statement::Select(StatementSelect::new("users").filter(Some(Condition::IsNull("id".into()))));
+        assert_eq!(export_csv(&mut db, &select, &mut out).unwrap(), 0);
+        assert_eq!(String::from_utf8(out).unwrap(), "id,age,born\n");
+    }
+}
@@ -0,0 +1,422 @@
+//  DUMP.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:26:01
+//  Last edited:
+//    18 Oct 2026, 14:41:11
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements logical dumps, which write the schema and data of a
+//!   database to a portable SQL file that can be restored into any
+//!   backend.
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::io::{Read, Write};
+
+use enum_debug::EnumDebug as _;
+use log::{debug, info, warn};
+
+use crate::spec::{cast_to_columns, order_by_foreign_keys, Database, Executor};
+use crate::sql::{serialize_sql, Dialect, Order, Statement, StatementCreateIndex, StatementCreateTable, StatementInsert, StatementSelect, Value};
+
+
+/***** ERRORS *****/
+/// Defines errors originating from dumping or restoring databases.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read the schema or rows of the database.
+    IntrospectFailed { err: Box<dyn Send + Sync + error::Error> },
+    /// Failed to write the dump.
+    Write { err: std::io::Error },
+    /// Failed to read the dump.
+    Read { err: std::io::Error },
+    /// Failed to execute a statement of the dump.
+    ExecuteFailed { line: usize, err: Box<dyn Send + Sync + error::Error> },
+    /// Failed to begin or commit the transaction in which the dump is made or restored.
+    TransactionFailed { err: Box<dyn Send + Sync + error::Error> },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            IntrospectFailed { .. } => write!(f, "Failed to read database"),
+            Write { .. } => write!(f, "Failed to write dump"),
+            Read { .. } => write!(f, "Failed to read dump"),
+            ExecuteFailed { line, .. } => write!(f, "Failed to execute statement on line {line} of dump"),
+            TransactionFailed { .. } => write!(f, "Failed to begin or commit transaction"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            IntrospectFailed { err } => Some(&**err),
+            Write { err } => Some(err),
+            Read { err } => Some(err),
+            ExecuteFailed { err, .. } => Some(&**err),
+            TransactionFailed { err } => Some(&**err),
+        }
+    }
+}
+
+/// Separates errors of the backend from our own while running in a transaction.
+enum Failure<E> {
+    /// The backend failed.
+    Backend(E),
+    /// We failed.
+    Dump(Error),
+}
+impl<E> From<E> for Failure<E> {
+    #[inline]
+    fn from(value: E) -> Self { Self::Backend(value) }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Splits an SQL script into its statements.
+///
+/// # Arguments
+/// - `sql`: The script to split.
+/// - `dialect`: The [`Dialect`] the script is written in, which determines how strings are escaped and comments are written.
+///
+/// # Returns
+/// A list of the statements in the script, each with the (1-indexed) line on which it starts. Comments before statements are dropped.
+fn split_statements(sql: &str, dialect: Dialect) -> Vec<(usize, &str)> {
+    /// The state of the parser.
+    enum State {
+        Code,
+        Quoted(char),
+        LineComment,
+        BlockComment,
+    }
+
+    let mut stmts: Vec<(usize, &str)> = vec![];
+    let (mut state, mut start, mut line): (State, Option<(usize, usize)>, usize) = (State::Code, None, 1);
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        match state {
+            State::Code => match c {
+                ';' => {
+                    if let Some((start, start_line)) = start.take() {
+                        stmts.push((start_line, &sql[start..=i]));
+                    }
+                },
+                '-' if matches!(chars.peek(), Some((_, '-'))) => state = State::LineComment,
+                '#' if dialect == Dialect::MySql => state = State::LineComment,
+                '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                    chars.next();
+                    state = State::BlockComment;
+                },
+                c if c.is_whitespace() => {},
+                c => {
+                    start.get_or_insert((i, line));
+                    if c == '\'' || c == '"' || c == '`' {
+                        state = State::Quoted(c);
+                    }
+                },
+            },
+            State::Quoted(quote) => {
+                if c == quote {
+                    // Doubled quotes simply re-enter the string on the next character
+                    state = State::Code;
+                } else if c == '\\' && quote != '`' && dialect == Dialect::MySql {
+                    // MySQL also escapes with backslashes
+                    if let Some((_, '\n')) = chars.next() {
+                        line += 1;
+                    }
+                }
+            },
+            State::LineComment => {
+                if c == '\n' {
+                    state = State::Code;
+                }
+            },
+            State::BlockComment => {
+                if c == '*' && matches!(chars.peek(), Some((_, '/'))) {
+                    chars.next();
+                    state = State::Code;
+                }
+            },
+        }
+    }
+    if let Some((start, start_line)) = start {
+        stmts.push((start_line, sql[start..].trim_end()));
+    }
+    stmts
+}
+
+/// Writes a [`Statement`] to a dump.
+///
+/// # Arguments
+/// - `writer`: The [`Write`]r to write to.
+/// - `stmt`: The [`Statement`] to write.
+/// - `dialect`: The [`Dialect`] to write it in.
+///
+/// # Errors
+/// This function errors if we failed to write.
+#[inline]
+fn write_stmt(writer: &mut impl Write, stmt: &Statement, dialect: Dialect) -> Result<(), Error> {
+    match writeln!(writer, "{}", serialize_sql(stmt, dialect)) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(Error::Write { err }),
+    }
+}
+
+/// Writes a raw line to a dump.
+///
+/// # Arguments
+/// - `writer`: The [`Write`]r to write to.
+/// - `line`: The line to write.
+///
+/// # Errors
+/// This function errors if we failed to write.
+#[inline]
+fn write_line(writer: &mut impl Write, line: &str) -> Result<(), Error> {
+    match writeln!(writer, "{line}") {
+        Ok(()) => Ok(()),
+        Err(err) => Err(Error::Write { err }),
+    }
+}
+
+/// Writes the rows of a table to a dump as batched `INSERT`s.
+///
+/// # Arguments
+/// - `tx`: The [`Executor`] to read the rows with.
+/// - `table`: The definition of the table to write the rows of. Values are cast to the types of its columns where possible.
+/// - `dialect`: The [`Dialect`] to write the statements in.
+/// - `batch_size`: The maximum number of rows per statement.
+/// - `writer`: The [`Write`]r to write to.
+///
+/// # Returns
+/// The number of rows written.
+///
+/// # Errors
+/// This function errors if we failed to read the rows or write the statements.
+fn dump_rows<X: Executor>(
+    tx: &mut X,
+    table: &StatementCreateTable,
+    dialect: Dialect,
+    batch_size: usize,
+    writer: &mut impl Write,
+) -> Result<u64, Failure<X::Error>> {
+    // Select the columns explicitly, such that the values match the definitions
+    let mut select: StatementSelect = StatementSelect::new(&table.name).cols(table.cols.iter().map(|c| &c.name));
+    if let Some(key) = &table.primary_key {
+        select = select.order_by(key, Order::Ascending);
+    }
+
+    let mut n_rows: u64 = 0;
+    let mut batch: Vec<Vec<Value>> = Vec::with_capacity(batch_size.min(1024));
+    let mut flush = |batch: Vec<Vec<Value>>| -> Result<(), Failure<X::Error>> {
+        let insert = StatementInsert { rows: batch, ..StatementInsert::new(&table.name, table.cols.iter().map(|c| &c.name)) };
+        write_stmt(writer, &Statement::Insert(insert), dialect).map_err(Failure::Dump)
+    };
+    tx.for_each_row(&Statement::Select(select), |row| -> Result<(), Failure<X::Error>> {
+        batch.push(cast_to_columns(row.values, &table.cols));
+        n_rows += 1;
+        if batch.len() >= batch_size {
+            flush(std::mem::take(&mut batch))?;
+        }
+        Ok(())
+    })?;
+    if !batch.is_empty() {
+        flush(batch)?;
+    }
+    Ok(n_rows)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Writes the schema and data of a database to a portable SQL file.
+///
+/// The dump consists of `CREATE TABLE`-statements (ordered such that tables referred to by foreign keys come first), `CREATE
+/// INDEX`-statements and batched `INSERT`-statements for all rows, serialized in the given `dialect`. This means that a dump of an SQLite
+/// database can be restored into MySQL and vice versa (see [`restore()`]). Foreign key checks are deferred while restoring, such that
+/// rows can be inserted in any order.
+///
+/// The database is read in a single transaction, such that the dump is consistent.
+///
+/// Note that the dump is limited by what the backend remembers of the schema; e.g., SQLite stores most types as `TEXT` (see
+/// [`Executor::describe_table()`]).
+///
+/// # Arguments
+/// - `db`: The [`Database`] to dump.
+/// - `dialect`: The [`Dialect`] of the backend the dump is to be restored into.
+/// - `writer`: The [`Write`]r to write the dump to.
+/// - `batch_size`: The maximum number of rows per `INSERT`. Note that MySQL supports at most 65535 values per statement.
+///
+/// # Returns
+/// The number of rows dumped.
+///
+/// # Errors
+/// This function errors if we failed to read the database (e.g., because it has tables that cannot be described) or to write the dump.
+pub fn dump<D: Database>(db: &D, dialect: Dialect, mut writer: impl Write, batch_size: usize) -> Result<u64, Error> {
+    info!("Dumping database as {}", dialect.variant());
+    let batch_size: usize = batch_size.max(1);
+    let res: Result<u64, Failure<D::Error>> = db.transaction(|tx| {
+        // Read the schema
+        let mut tables: Vec<StatementCreateTable> = vec![];
+        let mut indexes: Vec<StatementCreateIndex> = vec![];
+        for name in tx.tables()? {
+            tables.push(tx.describe_table(&name)?);
+            indexes.extend(tx.indexes(&name)?);
+        }
+        let (mut tables, mut cyclic): (Vec<StatementCreateTable>, Vec<StatementCreateTable>) = order_by_foreign_keys(tables, |table| table);
+        if !cyclic.is_empty() {
+            warn!(
+                "Foreign keys between tables {} form a cycle; dumping them in alphabetical order",
+                cyclic.iter().map(|t| format!("'{}'", t.name)).collect::<Vec<String>>().join(", ")
+            );
+            tables.append(&mut cyclic);
+        }
+
+        // Write the header and the schema
+        let (defer_fks, restore_fks): (&str, Option<&str>) = match dialect {
+            Dialect::MySql => ("SET FOREIGN_KEY_CHECKS = 0;", Some("SET FOREIGN_KEY_CHECKS = 1;")),
+            // Reset automatically when the transaction commits
+            Dialect::Sqlite => ("PRAGMA defer_foreign_keys = ON;", None),
+        };
+        write_line(&mut writer, &format!("-- Dump of {} tables for {}", tables.len(), dialect.variant())).map_err(Failure::Dump)?;
+        write_line(&mut writer, defer_fks).map_err(Failure::Dump)?;
+        write_line(&mut writer, "").map_err(Failure::Dump)?;
+        for table in &tables {
+            write_stmt(&mut writer, &Statement::CreateTable(table.clone()), dialect).map_err(Failure::Dump)?;
+        }
+        for index in indexes {
+            write_stmt(&mut writer, &Statement::CreateIndex(index), dialect).map_err(Failure::Dump)?;
+        }
+
+        // Write the data
+        let mut n_rows: u64 = 0;
+        for table in &tables {
+            write_line(&mut writer, "").map_err(Failure::Dump)?;
+            let table_rows: u64 = dump_rows(tx, table, dialect, batch_size, &mut writer)?;
+            debug!("Dumped {table_rows} rows of table '{}'", table.name);
+            n_rows += table_rows;
+        }
+        if let Some(restore_fks) = restore_fks {
+            write_line(&mut writer, "").map_err(Failure::Dump)?;
+            write_line(&mut writer, restore_fks).map_err(Failure::Dump)?;
+        }
+        if let Err(err) = writer.flush() {
+            return Err(Failure::Dump(Error::Write { err }));
+        }
+        Ok(n_rows)
+    });
+    match res {
+        Ok(n_rows) => {
+            debug!("Dumped {n_rows} rows");
+            Ok(n_rows)
+        },
+        Err(Failure::Backend(err)) => Err(Error::IntrospectFailed { err: Box::new(err) }),
+        Err(Failure::Dump(err)) => Err(err),
+    }
+}
+
+/// Replays a dump written by [`dump()`] (or any other SQL script) on a database.
+///
+/// The statements are executed as-is in a single transaction, so the dump must be written in the dialect of the database. Note that
+/// MySQL implicitly commits after statements like `CREATE TABLE`, so a failed restore may leave tables behind there.
+///
+/// # Arguments
+/// - `db`: The [`Database`] to restore into.
+/// - `reader`: The [`Read`]er to read the dump from.
+///
+/// # Returns
+/// The number of statements executed.
+///
+/// # Errors
+/// This function errors if we failed to read the dump or to execute any of its statements.
+pub fn restore<D: Database>(db: &D, mut reader: impl Read) -> Result<usize, Error> {
+    let mut sql: String = String::new();
+    if let Err(err) = reader.read_to_string(&mut sql) {
+        return Err(Error::Read { err });
+    }
+    let stmts: Vec<(usize, &str)> = split_statements(&sql, D::DIALECT);
+    info!("Restoring dump of {} statements", stmts.len());
+
+    let res: Result<(), Failure<D::Error>> = db.transaction(|tx| {
+        for (line, stmt) in &stmts {
+            if let Err(err) = tx.execute_raw(stmt) {
+                return Err(Failure::Dump(Error::ExecuteFailed { line: *line, err: Box::new(err) }));
+            }
+        }
+        Ok(())
+    });
+    match res {
+        Ok(()) => Ok(stmts.len()),
+        Err(Failure::Backend(err)) => Err(Error::TransactionFailed { err: Box::new(err) }),
+        Err(Failure::Dump(err)) => Err(err),
+    }
+}
+
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn split_quoted_semicolons() {
+        let sql: &str = "INSERT INTO t VALUES ('a;b', 'it''s;');\nINSERT INTO t VALUES (\"c;\", `d;`);";
+        let stmts: Vec<(usize, &str)> = split_statements(sql, Dialect::Sqlite);
+        assert_eq!(stmts, vec![(1, "INSERT INTO t VALUES ('a;b', 'it''s;');"), (2, "INSERT INTO t VALUES (\"c;\", `d;`);")]);
+    }
+
+    #[test]
+    fn split_backslash_escapes() {
+        let sql: &str = "INSERT INTO t VALUES ('a\\';b');\nSELECT 1;";
+        // MySQL treats the backslash as an escape, so the string runs on to the second quote
+        let stmts: Vec<(usize, &str)> = split_statements(sql, Dialect::MySql);
+        assert_eq!(stmts, vec![(1, "INSERT INTO t VALUES ('a\\';b');"), (2, "SELECT 1;")]);
+        // SQLite does not, so the string ends at the escaped quote
+        let stmts: Vec<(usize, &str)> = split_statements(sql, Dialect::Sqlite);
+        assert_eq!(stmts, vec![(1, "INSERT INTO t VALUES ('a\\';"), (1, "b');\nSELECT 1;")]);
+
+        // Escaped newlines still count as lines
+        let sql: &str = "SELECT 'a\\\nb';\nSELECT 2;";
+        assert_eq!(split_statements(sql, Dialect::MySql), vec![(1, "SELECT 'a\\\nb';"), (3, "SELECT 2;")]);
+    }
+
+    #[test]
+    fn split_comments() {
+        let sql: &str = "-- a; comment\n/* a;\nblock */ SELECT 1; # mysql; comment\nSELECT 2 -- trailing;\n;";
+        let stmts: Vec<(usize, &str)> = split_statements(sql, Dialect::MySql);
+        assert_eq!(stmts, vec![(3, "SELECT 1;"), (4, "SELECT 2 -- trailing;\n;")]);
+
+        // SQLite has no `#` comments
+        let sql: &str = "# not; a comment\nSELECT 1;";
+        let stmts: Vec<(usize, &str)> = split_statements(sql, Dialect::Sqlite);
+        assert_eq!(stmts, vec![(1, "# not;"), (1, "a comment\nSELECT 1;")]);
+    }
+
+    #[test]
+    fn split_lines_and_trailing() {
+        let sql: &str = "\n\nSELECT 1;\n\n  SELECT\n2;\nSELECT 3  \n\n";
+        let stmts: Vec<(usize, &str)> = split_statements(sql, Dialect::Sqlite);
+        assert_eq!(stmts, vec![(3, "SELECT 1;"), (5, "SELECT\n2;"), (7, "SELECT 3")]);
+
+        assert_eq!(split_statements("", Dialect::MySql), vec![]);
+        assert_eq!(split_statements("  -- only a comment\n", Dialect::MySql), vec![]);
+    }
+}
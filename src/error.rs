@@ -0,0 +1,255 @@
+//  ERROR.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 13:02:07
+//  Last edited:
+//    18 Oct 2026, 14:43:58
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a crate-level [`Error`] that wraps the errors of every
+//!   backend, and that can be classified independently of them.
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+#[cfg(feature = "mysql")]
+use crate::mysql;
+#[cfg(feature = "sqlite")]
+use crate::sqlite;
+
+
+/***** LIBRARY *****/
+/// Defines errors originating in any of the databases in this crate.
+///
+/// Use the `is_*()`-methods to find out what went wrong without matching on the backend, e.g., to decide whether to
+/// retry a transaction:
+/// ```ignore
+/// match db.execute(&stmt).map_err(Error::from) {
+///     Ok(_) => {},
+///     Err(err) if err.is_deadlock() || err.is_timeout() => { /* retry */ },
+///     Err(err) => return Err(err),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to load a config file.
+    Config { err: crate::common::Error },
+    /// The MySQL backend failed.
+    #[cfg(feature = "mysql")]
+    MySql { err: mysql::Error },
+    /// The SQLite backend failed.
+    #[cfg(feature = "sqlite")]
+    Sqlite { err: sqlite::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            Config { .. } => write!(f, "Failed to load database configuration file"),
+            #[cfg(feature = "mysql")]
+            MySql { .. } => write!(f, "MySQL backend failed"),
+            #[cfg(feature = "sqlite")]
+            Sqlite { .. } => write!(f, "SQLite backend failed"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            Config { err } => Some(err),
+            #[cfg(feature = "mysql")]
+            MySql { err } => Some(err),
+            #[cfg(feature = "sqlite")]
+            Sqlite { err } => Some(err),
+        }
+    }
+}
+impl Error {
+    /// Returns whether this error means that we failed to connect to the database, or lost the connection to it.
+    pub fn is_connection(&self) -> bool {
+        match self {
+            Self::Config { .. } => false,
+            #[cfg(feature = "mysql")]
+            Self::MySql { err } => err.is_connection(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite { err } => err.is_connection(),
+        }
+    }
+
+    /// Returns whether this error means that a statement violated a constraint, e.g., a unique key, foreign key or `NOT NULL`.
+    pub fn is_constraint_violation(&self) -> bool {
+        match self {
+            Self::Config { .. } => false,
+            #[cfg(feature = "mysql")]
+            Self::MySql { err } => err.is_constraint_violation(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite { err } => err.is_constraint_violation(),
+        }
+    }
+
+    /// Returns whether this error means that a statement violated a unique key (including a primary key).
+    ///
+    /// Every unique violation is also a constraint violation (see [`Error::is_constraint_violation()`]).
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            Self::Config { .. } => false,
+            #[cfg(feature = "mysql")]
+            Self::MySql { err } => err.is_unique_violation(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite { err } => err.is_unique_violation(),
+        }
+    }
+
+    /// Returns whether this error means that the transaction deadlocked with another one.
+    pub fn is_deadlock(&self) -> bool {
+        match self {
+            Self::Config { .. } => false,
+            #[cfg(feature = "mysql")]
+            Self::MySql { err } => err.is_deadlock(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite { err } => err.is_deadlock(),
+        }
+    }
+
+    /// Returns whether this error means that something timed out, e.g., connecting or waiting for a lock.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Self::Config { .. } => false,
+            #[cfg(feature = "mysql")]
+            Self::MySql { err } => err.is_timeout(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite { err } => err.is_timeout(),
+        }
+    }
+}
+
+impl From<crate::common::Error> for Error {
+    #[inline]
+    fn from(value: crate::common::Error) -> Self { Self::Config { err: value } }
+}
+#[cfg(feature = "mysql")]
+impl From<mysql::Error> for Error {
+    #[inline]
+    fn from(value: mysql::Error) -> Self { Self::MySql { err: value } }
+}
+#[cfg(feature = "sqlite")]
+impl From<sqlite::Error> for Error {
+    #[inline]
+    fn from(value: sqlite::Error) -> Self { Self::Sqlite { err: value } }
+}
+
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    use super::*;
+
+
+    /// Wraps a MySQL server error with the given code and SQLSTATE in an [`Error`].
+    #[cfg(feature = "mysql")]
+    fn mysql_err(code: u16, state: &str) -> Error {
+        let err: ::mysql::MySqlError = ::mysql::MySqlError { state: state.into(), message: format!("Error {code}"), code };
+        let err: ::mysql::Error = ::mysql::Error::MySqlError(err);
+        Error::from(mysql::Error::ExecuteFailed { query: "SELECT 1".into(), err })
+    }
+
+    /// Wraps an SQLite error with the given (primary or extended) result code and message in an [`Error`].
+    #[cfg(feature = "sqlite")]
+    fn sqlite_err(code: isize, message: &str) -> Error {
+        let err: ::sqlite::Error = ::sqlite::Error { code: Some(code), message: Some(message.into()) };
+        Error::from(sqlite::Error::ExecuteFailed { query: "SELECT 1".into(), err })
+    }
+
+    /// Lists which of the classifiers match the given error, in the order connection, constraint, unique, deadlock and timeout.
+    #[cfg(any(feature = "mysql", feature = "sqlite"))]
+    fn classes(err: &Error) -> [bool; 5] {
+        [err.is_connection(), err.is_constraint_violation(), err.is_unique_violation(), err.is_deadlock(), err.is_timeout()]
+    }
+
+
+    #[cfg(feature = "mysql")]
+    #[test]
+    fn mysql_classes() {
+        // ER_DUP_ENTRY
+        assert_eq!(classes(&mysql_err(1062, "23000")), [false, true, true, false, false]);
+        // ER_NO_REFERENCED_ROW_2 and ER_BAD_NULL_ERROR
+        assert_eq!(classes(&mysql_err(1452, "23000")), [false, true, false, false, false]);
+        assert_eq!(classes(&mysql_err(1048, "23000")), [false, true, false, false, false]);
+        // ER_LOCK_DEADLOCK
+        assert_eq!(classes(&mysql_err(1213, "40001")), [false, false, false, true, false]);
+        // ER_LOCK_WAIT_TIMEOUT and ER_QUERY_TIMEOUT
+        assert_eq!(classes(&mysql_err(1205, "HY000")), [false, false, false, false, true]);
+        assert_eq!(classes(&mysql_err(3024, "HY000")), [false, false, false, false, true]);
+        // ER_CON_COUNT_ERROR, CR_CONNECTION_ERROR, CR_CONN_HOST_ERROR, CR_SERVER_GONE_ERROR and CR_SERVER_LOST
+        for code in [1040, 2002, 2003, 2006, 2013] {
+            assert_eq!(classes(&mysql_err(code, "HY000")), [true, false, false, false, false], "code {code}");
+        }
+        // ER_PARSE_ERROR is none of them
+        assert_eq!(classes(&mysql_err(1064, "42000")), [false; 5]);
+    }
+
+    #[cfg(feature = "mysql")]
+    #[test]
+    fn mysql_connection_classes() {
+        // Failing to reach the server is a connection error regardless of the cause
+        let err: ::mysql::Error = ::mysql::Error::DriverError(::mysql::DriverError::ConnectTimeout);
+        assert_eq!(classes(&Error::from(mysql::Error::ConnectionGet { err })), [true, false, false, false, true]);
+        let err: ::mysql::Error = ::mysql::Error::IoError(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert_eq!(classes(&Error::from(mysql::Error::QueryFailed { query: "SELECT 1".into(), err })), [true, false, false, false, false]);
+        let err: ::mysql::Error = ::mysql::Error::IoError(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert_eq!(classes(&Error::from(mysql::Error::QueryFailed { query: "SELECT 1".into(), err })), [true, false, false, false, true]);
+
+        // Errors that don't come from the driver are none of them
+        assert_eq!(classes(&Error::from(mysql::Error::PoolSize { min: 2, max: 1 })), [false; 5]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_classes() {
+        // SQLITE_BUSY and SQLITE_BUSY_SNAPSHOT
+        assert_eq!(classes(&sqlite_err(5, "database is locked")), [false, false, false, false, true]);
+        assert_eq!(classes(&sqlite_err(517, "database is locked")), [false, false, false, false, true]);
+        // SQLITE_LOCKED
+        assert_eq!(classes(&sqlite_err(6, "database table is locked")), [false, false, false, true, false]);
+        // SQLITE_CANTOPEN
+        assert_eq!(classes(&sqlite_err(14, "unable to open database file")), [true, false, false, false, false]);
+
+        // SQLITE_CONSTRAINT, which only tells unique violations apart by its message
+        assert_eq!(classes(&sqlite_err(19, "UNIQUE constraint failed: t.name")), [false, true, true, false, false]);
+        assert_eq!(classes(&sqlite_err(19, "NOT NULL constraint failed: t.name")), [false, true, false, false, false]);
+        // SQLITE_CONSTRAINT_UNIQUE, SQLITE_CONSTRAINT_PRIMARYKEY and SQLITE_CONSTRAINT_FOREIGNKEY
+        assert_eq!(classes(&sqlite_err(2067, "")), [false, true, true, false, false]);
+        assert_eq!(classes(&sqlite_err(1555, "")), [false, true, true, false, false]);
+        assert_eq!(classes(&sqlite_err(787, "FOREIGN KEY constraint failed")), [false, true, false, false, false]);
+
+        // SQLITE_ERROR is none of them
+        assert_eq!(classes(&sqlite_err(1, "no such table: t")), [false; 5]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_unique_violation() {
+        let db: sqlite::Database = sqlite::Database::in_memory(|db| {
+            db.execute_raw("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE); INSERT INTO t VALUES (1, 'a');")
+        })
+        .unwrap();
+        let err: Error = db.execute_raw("INSERT INTO t VALUES (2, 'a');").unwrap_err().into();
+        assert_eq!(classes(&err), [false, true, true, false, false]);
+        let err: Error = db.execute_raw("INSERT INTO t VALUES (1, 'b');").unwrap_err().into();
+        assert_eq!(classes(&err), [false, true, true, false, false]);
+        let err: Error = db.execute_raw("INSERT INTO t (id) VALUES (3);").unwrap_err().into();
+        assert_eq!(classes(&err), [false, true, false, false, false]);
+        let err: Error = db.execute_raw("INSERT INTO u VALUES (1);").unwrap_err().into();
+        assert_eq!(classes(&err), [false; 5]);
+    }
+}
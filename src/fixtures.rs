@@ -0,0 +1,321 @@
+//  FIXTURES.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:17:29
+//  Last edited:
+//    18 Oct 2026, 14:05:50
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines [`Fixtures`], which load rows from files into existing
+//!   tables, e.g., for tests or demo environments.
+//
+
+use std::collections::BTreeMap;
+use std::error;
+#[cfg(feature = "csv")]
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter, Result as FResult};
+#[cfg(feature = "csv")]
+use std::io::Read;
+use std::path::Path;
+#[cfg(feature = "csv")]
+use std::path::PathBuf;
+
+use log::{debug, info};
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::load_config_file;
+use crate::schema::Seed;
+use crate::spec::{order_by_foreign_keys, Database, Executor as _};
+use crate::sql::{Ident, Statement, StatementCreateTable, Value};
+
+
+/***** ERRORS *****/
+/// Defines errors originating from [`Fixtures`].
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to load the fixture file.
+    FileLoad { err: crate::common::Error },
+    /// Failed to read a CSV fixture file.
+    #[cfg(feature = "csv")]
+    CsvRead { path: PathBuf, err: csv::Error },
+    /// The rows of a table were invalid.
+    SeedInvalid { err: crate::schema::Error },
+    /// The foreign keys between the fixtures' tables form a cycle, so they cannot be inserted in order.
+    CyclicForeignKeys { tables: Vec<Ident> },
+    /// Failed to insert the fixtures in the database.
+    ApplyFailed { err: Box<dyn Send + Sync + error::Error> },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            FileLoad { .. } => write!(f, "Failed to load fixture file"),
+            #[cfg(feature = "csv")]
+            CsvRead { path, .. } => write!(f, "Failed to read CSV fixture file '{}'", path.display()),
+            SeedInvalid { .. } => write!(f, "Invalid fixture"),
+            CyclicForeignKeys { tables } => write!(
+                f,
+                "Foreign keys between tables {} form a cycle",
+                tables.iter().map(|t| format!("'{t}'")).collect::<Vec<String>>().join(", ")
+            ),
+            ApplyFailed { .. } => write!(f, "Failed to insert fixtures"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            FileLoad { err } => Some(err),
+            #[cfg(feature = "csv")]
+            CsvRead { err, .. } => Some(err),
+            SeedInvalid { err } => Some(err),
+            CyclicForeignKeys { .. } => None,
+            ApplyFailed { err } => Some(&**err),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines rows to insert into existing tables.
+///
+/// In files, these are written as a map of table names to lists of rows, e.g.:
+/// ```yaml
+/// users:
+/// - { id: 1, name: admin }
+/// posts:
+/// - { author: 1, body: Hello, world! }
+/// ```
+///
+/// With the `csv`-feature, fixtures can also be read from CSV files with a header, where the file name determines the table.
+#[derive(Clone, Debug, Default)]
+pub struct Fixtures {
+    /// The rows to insert per table.
+    pub seeds: Vec<Seed>,
+}
+impl Fixtures {
+    /// Loads Fixtures from the given path.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the JSON, YAML or TOML file to load. With the `csv`-feature, this may also be a CSV file (see
+    ///   [`Fixtures::from_csv()`]), which is inserted into the table with the name of the file (e.g., `users.csv` into `users`).
+    ///
+    /// # Returns
+    /// New Fixtures.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the given file or if it is not a valid fixture file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path: &Path = path.as_ref();
+
+        #[cfg(feature = "csv")]
+        if path.extension().map(|ext| ext == OsStr::new("csv")).unwrap_or(false) {
+            debug!("Fixture file '{}' is CSV", path.display());
+            let table: Ident = path.file_stem().unwrap_or_default().to_string_lossy().into_owned().into();
+            return match csv::Reader::from_path(path) {
+                Ok(reader) => Self::from_csv(table, reader),
+                Err(err) => Err(Error::CsvRead { path: path.into(), err }),
+            };
+        }
+
+        match load_config_file(path) {
+            Ok(fixtures) => Ok(fixtures),
+            Err(err) => Err(Error::FileLoad { err }),
+        }
+    }
+
+    /// Reads Fixtures for a single table from a CSV file.
+    ///
+    /// The first record is expected to be a header with the names of the columns. All values are read as strings, which are cast to the
+    /// types of their columns when inserted (see [`Value::cast()`]), except that empty fields are read as NULL.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to insert the rows into.
+    /// - `reader`: The [`csv::Reader`] to read from.
+    ///
+    /// # Returns
+    /// New Fixtures.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the CSV file.
+    #[cfg(feature = "csv")]
+    pub fn from_csv(table: impl Into<Ident>, mut reader: csv::Reader<impl Read>) -> Result<Self, Error> {
+        let table: Ident = table.into();
+        let path: PathBuf = PathBuf::from(format!("{table}.csv"));
+
+        // Read the header
+        let cols: Vec<Ident> = match reader.headers() {
+            Ok(header) => header.iter().map(Ident::from).collect(),
+            Err(err) => return Err(Error::CsvRead { path, err }),
+        };
+
+        // Read the rows
+        let mut rows: Vec<BTreeMap<Ident, Value>> = vec![];
+        for record in reader.records() {
+            let record: csv::StringRecord = match record {
+                Ok(record) => record,
+                Err(err) => return Err(Error::CsvRead { path, err }),
+            };
+            rows.push(
+                cols.iter()
+                    .cloned()
+                    .zip(record.iter().map(|field| if field.is_empty() { Value::Null } else { Value::String(field.into()) }))
+                    .collect(),
+            );
+        }
+        Ok(Self { seeds: vec![Seed { table, rows }] })
+    }
+
+    /// Adds the rows of other Fixtures to these.
+    ///
+    /// # Arguments
+    /// - `other`: The other Fixtures to add, which are inserted after these for the same table.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.seeds.extend(other.seeds);
+        self
+    }
+
+    /// Returns the statements that insert these fixtures.
+    ///
+    /// Tables are inserted in an order such that any rows referred to by a foreign key are inserted first. Otherwise, the order of
+    /// the fixtures is kept.
+    ///
+    /// # Arguments
+    /// - `tables`: The definitions of the tables to insert into (e.g., those of a [`Schema`](crate::schema::Schema)). These are used to
+    ///   find the foreign keys, and to cast the values to the types of their columns (see [`Value::cast()`]).
+    ///
+    /// # Returns
+    /// A list of [`Statement`]s to execute in order.
+    ///
+    /// # Errors
+    /// This function errors if the fixtures refer to unknown tables or columns, if any of the values do not fit their columns, or if
+    /// the foreign keys between the tables form a cycle.
+    pub fn statements(&self, tables: &[StatementCreateTable]) -> Result<Vec<Statement>, Error> {
+        // Find the definitions of the tables we insert into
+        let mut todo: Vec<(&Seed, &StatementCreateTable)> = Vec::with_capacity(self.seeds.len());
+        for seed in &self.seeds {
+            match tables.iter().find(|t| t.name == seed.table) {
+                Some(table) => todo.push((seed, table)),
+                None => return Err(Error::SeedInvalid { err: crate::schema::Error::UnknownTable { table: seed.table.clone() } }),
+            }
+        }
+
+        // Insert tables that are referred to first
+        let (todo, cyclic): (Vec<_>, Vec<_>) = order_by_foreign_keys(todo, |(_, table)| table);
+        if !cyclic.is_empty() {
+            let mut tables: Vec<Ident> = cyclic.into_iter().map(|(seed, _)| seed.table.clone()).collect();
+            tables.sort();
+            tables.dedup();
+            return Err(Error::CyclicForeignKeys { tables });
+        }
+        let mut stmts: Vec<Statement> = vec![];
+        for (seed, table) in todo {
+            match seed.statements(table) {
+                Ok(inserts) => stmts.extend(inserts.into_iter().map(Statement::Insert)),
+                Err(err) => return Err(Error::SeedInvalid { err }),
+            }
+        }
+
+        debug!("Fixtures resolve to {} statements", stmts.len());
+        Ok(stmts)
+    }
+
+    /// Inserts these fixtures into the given database in a single transaction.
+    ///
+    /// # Arguments
+    /// - `db`: The [`Database`] to insert into.
+    /// - `tables`: The definitions of the tables to insert into. See [`Fixtures::statements()`].
+    ///
+    /// # Errors
+    /// This function errors if the fixtures are invalid (see [`Fixtures::statements()`]) or if we failed to insert them. In the latter
+    /// case, none of the fixtures are inserted.
+    pub fn apply<D: Database>(&self, db: &D, tables: &[StatementCreateTable]) -> Result<(), Error> {
+        let stmts: Vec<Statement> = self.statements(tables)?;
+        info!("Inserting fixtures for {} tables", self.seeds.len());
+        let res: Result<(), Box<dyn Send + Sync + error::Error>> = db.transaction(|tx| {
+            for stmt in &stmts {
+                tx.execute_prepared(stmt)?;
+            }
+            Ok(())
+        });
+        match res {
+            Ok(()) => Ok(()),
+            Err(err) => Err(Error::ApplyFailed { err }),
+        }
+    }
+}
+impl Serialize for Fixtures {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Merge the rows of the same table, as a map can only have the table once, but keep the order of the tables
+        let mut tables: Vec<(&Ident, Vec<&BTreeMap<Ident, Value>>)> = Vec::with_capacity(self.seeds.len());
+        for seed in &self.seeds {
+            match tables.iter_mut().find(|(table, _)| *table == &seed.table) {
+                Some((_, rows)) => rows.extend(&seed.rows),
+                None => tables.push((&seed.table, seed.rows.iter().collect())),
+            }
+        }
+        let mut map: S::SerializeMap = serializer.serialize_map(Some(tables.len()))?;
+        for (table, rows) in tables {
+            map.serialize_entry(table, &rows)?;
+        }
+        map.end()
+    }
+}
+impl<'de> Deserialize<'de> for Fixtures {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Visitor for the [`Fixtures`], which keeps the tables in the order in which they appear.
+        struct FixturesVisitor;
+        impl<'de> Visitor<'de> for FixturesVisitor {
+            type Value = Fixtures;
+
+            #[inline]
+            fn expecting(&self, f: &mut Formatter) -> FResult { write!(f, "a map of table names to lists of rows") }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut seeds: Vec<Seed> = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some((table, rows)) = map.next_entry::<Ident, Vec<BTreeMap<Ident, Value>>>()? {
+                    seeds.push(Seed { table, rows });
+                }
+                Ok(Fixtures { seeds })
+            }
+        }
+
+        deserializer.deserialize_map(FixturesVisitor)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_order() {
+        let mut fixtures: Fixtures = serde_yaml::from_str("posts:\n- { id: 1 }\nusers:\n- { id: 2 }\nauthors: []\n").unwrap();
+        let tables: Vec<&str> = fixtures.seeds.iter().map(|seed| seed.table.as_str()).collect();
+        assert_eq!(tables, ["posts", "users", "authors"]);
+
+        // Rows of the same table are merged at its first occurrence
+        fixtures.seeds.push(Seed { table: "posts".into(), rows: vec![BTreeMap::new()] });
+        assert_eq!(serde_yaml::to_string(&fixtures).unwrap(), "posts:\n- id: 1\n- {}\nusers:\n- id: 2\nauthors: []\n");
+    }
+}
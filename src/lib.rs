@@ -4,7 +4,7 @@
 //  Created:
 //    17 Dec 2023, 19:56:11
 //  Last edited:
-//    25 Dec 2023, 18:13:44
+//    18 Oct 2026, 13:07:12
 //  Auto updated?
 //    Yes
 //
@@ -21,5 +21,30 @@ pub mod sql;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 
+// Declare the async versions of the databases
+#[cfg(all(feature = "async", feature = "mysql"))]
+pub mod async_mysql;
+#[cfg(all(feature = "async", feature = "sqlite"))]
+pub mod async_sqlite;
+
 // Declare other modules
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub mod any;
 pub mod common;
+#[cfg(feature = "sql")]
+pub mod copy;
+#[cfg(feature = "csv")]
+pub mod csv_io;
+#[cfg(feature = "sql")]
+pub mod dump;
+pub mod error;
+#[cfg(feature = "sql")]
+pub mod fixtures;
+pub mod retry;
+#[cfg(feature = "sql")]
+pub mod schema;
+#[cfg(feature = "sql")]
+pub mod spec;
+
+// Bring the crate-level error into the root
+pub use error::Error;
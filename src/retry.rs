@@ -0,0 +1,518 @@
+//  RETRY.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 13:07:12
+//  Last edited:
+//    18 Oct 2026, 14:42:07
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a [`RetryPolicy`] that retries operations that failed
+//!   because of transient errors, such as deadlocks or dropped
+//!   connections.
+//
+
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::hash::{BuildHasher as _, Hasher as _};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "mysql")]
+use crate::mysql;
+#[cfg(feature = "sqlite")]
+use crate::sqlite;
+
+
+/***** DEFAULTS *****/
+/// Determines how often an operation is attempted when the user specifies nothing (i.e., it is not retried).
+const fn default_max_attempts() -> u32 { 1 }
+
+/// Determines the backoff before the first retry when the user specifies none, in milliseconds.
+const fn default_initial_backoff() -> u64 { 50 }
+
+/// Determines the maximum backoff between retries when the user specifies none, in milliseconds.
+const fn default_max_backoff() -> u64 { 5000 }
+
+/// Determines the factor by which the backoff grows with every retry when the user specifies none.
+const fn default_multiplier() -> f64 { 2.0 }
+
+/// Determines whether to randomize the backoff when the user specifies nothing.
+const fn default_jitter() -> bool { true }
+
+/// Determines which errors are retried when the user specifies none.
+fn default_retry_on() -> Vec<RetryOn> { vec![RetryOn::Connection, RetryOn::Deadlock, RetryOn::Timeout] }
+
+
+
+
+
+/***** HELPERS *****/
+/// Returns a random number in `[0, 1)`, used to add jitter to backoffs.
+///
+/// This does not need to be cryptographically secure; it only needs to keep concurrent clients from retrying in lockstep. Every
+/// [`RandomState`] is keyed differently, so we get that without pulling in a random number generator.
+fn random_unit() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0));
+    // Keep the 53 bits that fit in the mantissa of an f64
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines errors that can be classified as transient, such that a [`RetryPolicy`] knows whether to retry them.
+///
+/// This is implemented for the errors of every backend and the crate-level [`Error`](crate::Error). Implement it for your own error
+/// type to use it in the closures given to the `transaction_with_retry()`-methods of the databases.
+pub trait Retryable {
+    /// Returns whether this error means that we failed to connect to the database, or lost the connection to it.
+    fn is_connection(&self) -> bool;
+
+    /// Returns whether this error means that the transaction deadlocked with another one.
+    fn is_deadlock(&self) -> bool;
+
+    /// Returns whether this error means that something timed out, e.g., connecting or waiting for a lock.
+    fn is_timeout(&self) -> bool;
+}
+impl Retryable for crate::Error {
+    #[inline]
+    fn is_connection(&self) -> bool { crate::Error::is_connection(self) }
+
+    #[inline]
+    fn is_deadlock(&self) -> bool { crate::Error::is_deadlock(self) }
+
+    #[inline]
+    fn is_timeout(&self) -> bool { crate::Error::is_timeout(self) }
+}
+#[cfg(feature = "mysql")]
+impl Retryable for mysql::Error {
+    #[inline]
+    fn is_connection(&self) -> bool { mysql::Error::is_connection(self) }
+
+    #[inline]
+    fn is_deadlock(&self) -> bool { mysql::Error::is_deadlock(self) }
+
+    #[inline]
+    fn is_timeout(&self) -> bool { mysql::Error::is_timeout(self) }
+}
+#[cfg(feature = "sqlite")]
+impl Retryable for sqlite::Error {
+    #[inline]
+    fn is_connection(&self) -> bool { sqlite::Error::is_connection(self) }
+
+    #[inline]
+    fn is_deadlock(&self) -> bool { sqlite::Error::is_deadlock(self) }
+
+    #[inline]
+    fn is_timeout(&self) -> bool { sqlite::Error::is_timeout(self) }
+}
+
+
+
+/// Defines the classes of errors that a [`RetryPolicy`] may retry.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// Failing to connect to the database, or losing the connection to it (see [`Retryable::is_connection()`]).
+    Connection,
+    /// Deadlocks (see [`Retryable::is_deadlock()`]).
+    Deadlock,
+    /// Timeouts, e.g., waiting for a lock or, in SQLite, a busy database (see [`Retryable::is_timeout()`]).
+    Timeout,
+}
+
+
+
+/// Defines how operations that fail with transient errors are retried.
+///
+/// Between attempts, the policy waits for a backoff that starts at `initial_backoff` and is multiplied by `multiplier` after every
+/// retry, up to `max_backoff`. With `jitter`, a random amount of up to half of it is subtracted, such that concurrent clients don't
+/// retry in lockstep.
+///
+/// By default, operations are attempted only once. In a config file, it is given as a `retry` section, e.g.:
+/// ```yaml
+/// retry:
+///   max_attempts: 5
+///   initial_backoff: 20
+///   retry_on: [ deadlock, timeout ]
+/// ```
+///
+/// Note that retrying a statement after losing the connection may run it twice if the connection was lost after the server ran it.
+/// Leave out [`RetryOn::Connection`] if that is a problem, or use transactions.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RetryPolicy {
+    /// How often an operation is attempted in total, including the first try. Values below 1 are treated as 1.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts:    u32,
+    /// How long to wait before the first retry, in milliseconds.
+    #[serde(default = "default_initial_backoff")]
+    pub initial_backoff: u64,
+    /// The maximum time to wait between retries, in milliseconds.
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff:     u64,
+    /// The factor by which the backoff grows with every retry.
+    #[serde(default = "default_multiplier")]
+    pub multiplier:      f64,
+    /// Whether to randomize the backoff.
+    #[serde(default = "default_jitter")]
+    pub jitter:          bool,
+    /// The classes of errors to retry. Any other error is returned immediately.
+    #[serde(default = "default_retry_on")]
+    pub retry_on:        Vec<RetryOn>,
+}
+impl Default for RetryPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_attempts:    default_max_attempts(),
+            initial_backoff: default_initial_backoff(),
+            max_backoff:     default_max_backoff(),
+            multiplier:      default_multiplier(),
+            jitter:          default_jitter(),
+            retry_on:        default_retry_on(),
+        }
+    }
+}
+impl RetryPolicy {
+    /// Constructor for a RetryPolicy that never retries.
+    ///
+    /// # Returns
+    /// A new RetryPolicy that attempts every operation once.
+    #[inline]
+    pub fn none() -> Self { Self::default() }
+
+    /// Constructor for a RetryPolicy that retries the default classes of errors with the default backoff.
+    ///
+    /// # Arguments
+    /// - `max_attempts`: How often an operation is attempted in total, including the first try.
+    ///
+    /// # Returns
+    /// A new RetryPolicy that retries connection errors, deadlocks and timeouts.
+    #[inline]
+    pub fn new(max_attempts: u32) -> Self { Self { max_attempts, ..Self::default() } }
+
+    /// Sets the backoff between retries.
+    ///
+    /// # Arguments
+    /// - `initial`: How long to wait before the first retry.
+    /// - `max`: The maximum time to wait between retries.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial.as_millis() as u64;
+        self.max_backoff = max.as_millis() as u64;
+        self
+    }
+
+    /// Sets the factor by which the backoff grows with every retry.
+    ///
+    /// # Arguments
+    /// - `multiplier`: The factor to multiply the backoff with.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets whether to randomize the backoff.
+    ///
+    /// # Arguments
+    /// - `jitter`: Whether to subtract a random amount of up to half of the backoff.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the classes of errors to retry.
+    ///
+    /// # Arguments
+    /// - `retry_on`: The [`RetryOn`]s to retry. Any other error is returned immediately.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn retry_on(mut self, retry_on: impl IntoIterator<Item = RetryOn>) -> Self {
+        self.retry_on = retry_on.into_iter().collect();
+        self
+    }
+
+    /// Returns whether the given error should be retried under this policy.
+    ///
+    /// Note that this doesn't consider how often the operation was attempted already.
+    ///
+    /// # Arguments
+    /// - `err`: The error to classify.
+    ///
+    /// # Returns
+    /// True if `err` is in any of the classes in `retry_on`, or false otherwise.
+    pub fn retries(&self, err: &impl Retryable) -> bool {
+        self.retry_on.iter().any(|class| match class {
+            RetryOn::Connection => err.is_connection(),
+            RetryOn::Deadlock => err.is_deadlock(),
+            RetryOn::Timeout => err.is_timeout(),
+        })
+    }
+
+    /// Returns how long to wait before the given retry.
+    ///
+    /// # Arguments
+    /// - `retry`: The number of the retry, where the first retry is 1.
+    ///
+    /// # Returns
+    /// The [`Duration`] to wait, including any jitter.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let exp: i32 = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let mut backoff: f64 = (self.initial_backoff as f64 * self.multiplier.max(1.0).powi(exp)).min(self.max_backoff as f64);
+        if self.jitter {
+            backoff -= backoff / 2.0 * random_unit();
+        }
+        Duration::from_secs_f64(backoff.max(0.0) / 1000.0)
+    }
+
+    /// Decides whether to retry an operation after it failed, and logs it if so.
+    ///
+    /// # Arguments
+    /// - `attempt`: The number of the attempt that failed, where the first attempt is 1.
+    /// - `err`: The error with which it failed.
+    ///
+    /// # Returns
+    /// How long to wait before the next attempt, or [`None`] if the operation should not be retried.
+    pub(crate) fn backoff_after(&self, attempt: u32, err: &(impl Display + Retryable)) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.retries(err) {
+            return None;
+        }
+        let backoff: Duration = self.backoff_for(attempt);
+        warn!("Attempt {attempt}/{} failed: {err}; retrying in {}ms", self.max_attempts, backoff.as_millis());
+        Some(backoff)
+    }
+
+    /// Runs the given operation, retrying it as long as it fails with errors this policy retries.
+    ///
+    /// The backoff is slept on the current thread.
+    ///
+    /// # Arguments
+    /// - `op`: The operation to run. It is called again for every retry, so it must be safe to repeat.
+    ///
+    /// # Returns
+    /// The value returned by `op` once it succeeds.
+    ///
+    /// # Errors
+    /// This function errors with the error of `op` if it is not retried, or if the last attempt fails.
+    pub fn run<T, E>(&self, mut op: impl FnMut() -> Result<T, E>) -> Result<T, E>
+    where
+        E: Display + Retryable,
+    {
+        let mut attempt: u32 = 1;
+        loop {
+            let err: E = match op() {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+            match self.backoff_after(attempt, &err) {
+                Some(backoff) => thread::sleep(backoff),
+                None => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Runs the given asynchronous operation, retrying it as long as it fails with errors this policy retries.
+    ///
+    /// The backoff is slept with [`tokio::time::sleep()`], so this must be called from within a [`tokio`] runtime.
+    ///
+    /// # Arguments
+    /// - `op`: The operation to run. It is called again for every retry, so it must be safe to repeat.
+    ///
+    /// # Returns
+    /// The value returned by `op` once it succeeds.
+    ///
+    /// # Errors
+    /// This function errors with the error of `op` if it is not retried, or if the last attempt fails.
+    #[cfg(feature = "async")]
+    pub async fn run_async<T, E, F>(&self, mut op: impl FnMut() -> F) -> Result<T, E>
+    where
+        E: Display + Retryable,
+        F: Future<Output = Result<T, E>>,
+    {
+        let mut attempt: u32 = 1;
+        loop {
+            let err: E = match op().await {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+            match self.backoff_after(attempt, &err) {
+                Some(backoff) => tokio::time::sleep(backoff).await,
+                None => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+}
+
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Formatter, Result as FResult};
+
+    use super::*;
+
+
+    /// A [`Retryable`] error of a fixed class.
+    #[derive(Debug)]
+    struct TestError(Option<RetryOn>);
+    impl Display for TestError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Test error ({:?})", self.0) }
+    }
+    impl Retryable for TestError {
+        #[inline]
+        fn is_connection(&self) -> bool { self.0 == Some(RetryOn::Connection) }
+
+        #[inline]
+        fn is_deadlock(&self) -> bool { self.0 == Some(RetryOn::Deadlock) }
+
+        #[inline]
+        fn is_timeout(&self) -> bool { self.0 == Some(RetryOn::Timeout) }
+    }
+
+
+    #[test]
+    fn backoff_grows_exponentially() {
+        let policy: RetryPolicy = RetryPolicy::new(10).backoff(Duration::from_millis(10), Duration::from_secs(60)).jitter(false);
+        let backoffs: Vec<u128> = (1..=5).map(|retry| policy.backoff_for(retry).as_millis()).collect();
+        assert_eq!(backoffs, vec![10, 20, 40, 80, 160]);
+
+        let policy: RetryPolicy = policy.multiplier(3.0);
+        let backoffs: Vec<u128> = (1..=4).map(|retry| policy.backoff_for(retry).as_millis()).collect();
+        assert_eq!(backoffs, vec![10, 30, 90, 270]);
+
+        // Retry 0 is treated like the first retry
+        assert_eq!(policy.backoff_for(0), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy: RetryPolicy = RetryPolicy::new(10).backoff(Duration::from_millis(100), Duration::from_millis(500)).jitter(false);
+        let backoffs: Vec<u128> = (1..=6).map(|retry| policy.backoff_for(retry).as_millis()).collect();
+        assert_eq!(backoffs, vec![100, 200, 400, 500, 500, 500]);
+        // Even when the exponent overflows
+        assert_eq!(policy.backoff_for(u32::MAX), Duration::from_millis(500));
+
+        // The cap also applies to the first retry
+        let policy: RetryPolicy = policy.backoff(Duration::from_secs(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn backoff_never_shrinks() {
+        // Multipliers below 1 are treated as 1, i.e., a constant backoff
+        for multiplier in [0.5, 0.0, -2.0] {
+            let policy: RetryPolicy =
+                RetryPolicy::new(10).backoff(Duration::from_millis(40), Duration::from_secs(1)).multiplier(multiplier).jitter(false);
+            let backoffs: Vec<u128> = (1..=4).map(|retry| policy.backoff_for(retry).as_millis()).collect();
+            assert_eq!(backoffs, vec![40, 40, 40, 40], "multiplier {multiplier}");
+        }
+    }
+
+    #[test]
+    fn backoff_jitter() {
+        let policy: RetryPolicy = RetryPolicy::new(10).backoff(Duration::from_millis(100), Duration::from_secs(1)).jitter(true);
+        for retry in 1..=20 {
+            let backoff: Duration = policy.backoff_for(retry);
+            let max: Duration = Duration::from_millis(100 * 2u64.pow(retry.min(5) - 1)).min(Duration::from_secs(1));
+            assert!(backoff <= max && backoff >= max / 2, "retry {retry}: {backoff:?} not in [{:?}, {max:?}]", max / 2);
+        }
+    }
+
+    #[test]
+    fn retries_classes() {
+        let policy: RetryPolicy = RetryPolicy::new(3);
+        assert!(policy.retries(&TestError(Some(RetryOn::Connection))));
+        assert!(policy.retries(&TestError(Some(RetryOn::Deadlock))));
+        assert!(policy.retries(&TestError(Some(RetryOn::Timeout))));
+        assert!(!policy.retries(&TestError(None)));
+
+        let policy: RetryPolicy = policy.retry_on([RetryOn::Deadlock]);
+        assert!(!policy.retries(&TestError(Some(RetryOn::Connection))));
+        assert!(policy.retries(&TestError(Some(RetryOn::Deadlock))));
+        assert!(!policy.retries(&TestError(Some(RetryOn::Timeout))));
+
+        let policy: RetryPolicy = policy.retry_on([]);
+        assert!(!policy.retries(&TestError(Some(RetryOn::Deadlock))));
+    }
+
+    #[test]
+    fn backoff_after_stops() {
+        let policy: RetryPolicy =
+            RetryPolicy::new(3).backoff(Duration::from_millis(10), Duration::from_secs(1)).jitter(false).retry_on([RetryOn::Deadlock]);
+        let deadlock: TestError = TestError(Some(RetryOn::Deadlock));
+        assert_eq!(policy.backoff_after(1, &deadlock), Some(Duration::from_millis(10)));
+        assert_eq!(policy.backoff_after(2, &deadlock), Some(Duration::from_millis(20)));
+        assert_eq!(policy.backoff_after(3, &deadlock), None);
+        assert_eq!(policy.backoff_after(4, &deadlock), None);
+
+        // Other classes are never retried
+        assert_eq!(policy.backoff_after(1, &TestError(Some(RetryOn::Timeout))), None);
+        assert_eq!(policy.backoff_after(1, &TestError(None)), None);
+
+        // Neither is anything when attempting only once, or not at all
+        assert_eq!(RetryPolicy::none().backoff_after(1, &deadlock), None);
+        assert_eq!(RetryPolicy::new(0).backoff_after(1, &deadlock), None);
+    }
+
+    #[test]
+    fn run_retries() {
+        let policy: RetryPolicy = RetryPolicy::new(3).backoff(Duration::ZERO, Duration::ZERO).jitter(false);
+
+        // Succeeds on the last attempt
+        let mut attempts: u32 = 0;
+        let res: Result<u32, TestError> = policy.run(|| {
+            attempts += 1;
+            if attempts < 3 { Err(TestError(Some(RetryOn::Deadlock))) } else { Ok(attempts) }
+        });
+        assert_eq!(res.unwrap(), 3);
+
+        // Gives up after the last attempt
+        let mut attempts: u32 = 0;
+        let res: Result<(), TestError> = policy.run(|| {
+            attempts += 1;
+            Err(TestError(Some(RetryOn::Timeout)))
+        });
+        assert!(res.is_err());
+        assert_eq!(attempts, 3);
+
+        // Returns other errors immediately
+        let mut attempts: u32 = 0;
+        let res: Result<(), TestError> = policy.run(|| {
+            attempts += 1;
+            Err(TestError(None))
+        });
+        assert!(res.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
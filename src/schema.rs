@@ -0,0 +1,205 @@
+//  SCHEMA.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:14:33
+//  Last edited:
+//    18 Oct 2026, 12:17:29
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines declarative [`Schema`]s, which describe the tables, indices
+//!   and initial rows of a database such that they can be written in a
+//!   file and applied in one go.
+//
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::path::Path;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::common::load_config_file;
+use crate::sql::errors::ValueCastError;
+use crate::sql::{ColumnDef, Ident, Statement, StatementCreateIndex, StatementCreateTable, StatementInsert, Value};
+
+
+/***** ERRORS *****/
+/// Defines errors originating from [`Schema`]s.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to load the schema file.
+    FileLoad { err: crate::common::Error },
+    /// A seed refers to a table that is not defined in the schema.
+    UnknownTable { table: Ident },
+    /// A seed refers to a column that is not defined in its table.
+    UnknownColumn { table: Ident, col: Ident },
+    /// A seed value did not fit the type of its column.
+    IllegalValue { table: Ident, col: Ident, err: ValueCastError },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            FileLoad { .. } => write!(f, "Failed to load schema file"),
+            UnknownTable { table } => write!(f, "Cannot seed table '{table}' that is not defined in the schema"),
+            UnknownColumn { table, col } => write!(f, "Cannot seed column '{col}' that is not defined in table '{table}'"),
+            IllegalValue { table, col, .. } => write!(f, "Illegal seed value for column '{col}' in table '{table}'"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            FileLoad { err } => Some(err),
+            UnknownTable { .. } => None,
+            UnknownColumn { .. } => None,
+            IllegalValue { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes a whole database: its tables, indices and the rows it is seeded with.
+///
+/// It is typically loaded from a file using [`Schema::from_path()`], e.g.:
+/// ```yaml
+/// tables:
+/// - name: users
+///   columns:
+///   - { name: id, type: BIGINT UNSIGNED, auto_increment: true }
+///   - { name: name, type: VARCHAR(64), not_null: true }
+///   - { name: created, type: DATETIME, default: CURRENT_TIMESTAMP }
+///   primary_key: id
+/// - name: posts
+///   columns:
+///   - { name: id, type: BIGINT UNSIGNED, auto_increment: true }
+///   - { name: author, type: BIGINT UNSIGNED, not_null: true }
+///   - { name: body, type: TEXT }
+///   primary_key: id
+///   foreign_keys:
+///   - { columns: [ author ], references: users, referenced_columns: [ id ], on_delete: cascade }
+/// indexes:
+/// - { name: users_name, table: users, columns: [ name ], unique: true }
+/// seed:
+/// - table: users
+///   rows:
+///   - { name: admin }
+/// ```
+///
+/// Then, use the `apply_schema()`-function of the backend database to create it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Schema {
+    /// The tables to create, in order.
+    #[serde(default)]
+    pub tables:  Vec<StatementCreateTable>,
+    /// The indices to create once the tables are created.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indexes: Vec<StatementCreateIndex>,
+    /// The rows to insert once the tables are created.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed:    Vec<Seed>,
+}
+impl Schema {
+    /// Loads a Schema from the given path.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the JSON, YAML or TOML file to load.
+    ///
+    /// # Returns
+    /// A new Schema.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the given file or if it is not a valid schema.
+    #[inline]
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        match load_config_file(path) {
+            Ok(schema) => Ok(schema),
+            Err(err) => Err(Error::FileLoad { err }),
+        }
+    }
+
+    /// Returns the statements that create this schema.
+    ///
+    /// These are the tables, then the indices and then the seeded rows, where the values of the latter are cast to the types of
+    /// their columns (see [`Value::cast()`]).
+    ///
+    /// # Returns
+    /// A list of [`Statement`]s to execute in order.
+    ///
+    /// # Errors
+    /// This function errors if the seed refers to unknown tables or columns, or if any of its values do not fit their columns.
+    pub fn statements(&self) -> Result<Vec<Statement>, Error> {
+        let mut stmts: Vec<Statement> = Vec::with_capacity(self.tables.len() + self.indexes.len() + self.seed.len());
+        stmts.extend(self.tables.iter().cloned().map(Statement::CreateTable));
+        stmts.extend(self.indexes.iter().cloned().map(Statement::CreateIndex));
+
+        // Resolve the seed rows
+        for seed in &self.seed {
+            match self.tables.iter().find(|t| t.name == seed.table) {
+                Some(table) => stmts.extend(seed.statements(table)?.into_iter().map(Statement::Insert)),
+                None => return Err(Error::UnknownTable { table: seed.table.clone() }),
+            }
+        }
+
+        debug!("Schema resolves to {} statements", stmts.len());
+        Ok(stmts)
+    }
+}
+
+/// Describes the rows to insert into a table when a [`Schema`] is applied.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Seed {
+    /// The name of the table to insert into.
+    pub table: Ident,
+    /// The rows to insert, as a map of column names to values. Omitted columns get their default value.
+    pub rows:  Vec<BTreeMap<Ident, Value>>,
+}
+impl Seed {
+    /// Returns the statements that insert these rows.
+    ///
+    /// Consecutive rows with the same columns are inserted in the same statement.
+    ///
+    /// # Arguments
+    /// - `table`: The definition of the seeded table, used to cast the values to the types of their columns (see [`Value::cast()`]).
+    ///
+    /// # Returns
+    /// A list of [`StatementInsert`]s to execute in order.
+    ///
+    /// # Errors
+    /// This function errors if the rows refer to columns not in `table`, or if any of the values do not fit their columns.
+    pub fn statements(&self, table: &StatementCreateTable) -> Result<Vec<StatementInsert>, Error> {
+        let mut inserts: Vec<StatementInsert> = vec![];
+        for row in &self.rows {
+            let mut values: Vec<Value> = Vec::with_capacity(row.len());
+            for (col, value) in row {
+                let def: &ColumnDef = match table.cols.iter().find(|c| &c.name == col) {
+                    Some(def) => def,
+                    None => return Err(Error::UnknownColumn { table: self.table.clone(), col: col.clone() }),
+                };
+                match value.cast(&def.ty) {
+                    Ok(value) => values.push(value),
+                    Err(err) => return Err(Error::IllegalValue { table: self.table.clone(), col: col.clone(), err }),
+                }
+            }
+
+            // Group with the previous row if they have the same columns
+            match inserts.pop() {
+                Some(insert) if insert.cols.iter().eq(row.keys()) => inserts.push(insert.add_row(values)),
+                prev => {
+                    inserts.extend(prev);
+                    inserts.push(StatementInsert::new(&self.table, row.keys()).add_row(values));
+                },
+            }
+        }
+        Ok(inserts)
+    }
+}
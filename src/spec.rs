@@ -0,0 +1,274 @@
+//  SPEC.rs
+//    by Lut99
+//
+//  Created:
+//    18 Oct 2026, 12:17:29
+//  Last edited:
+//    18 Oct 2026, 14:05:50
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the interfaces shared by the various [`Database`]s, such
+//!   that code can be written independently of the backend.
+//
+
+use std::error::Error;
+
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+use log::warn;
+
+use crate::common::ExecuteResult;
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+use crate::sql::{parse_literal, Type};
+use crate::sql::{ColumnDef, Dialect, Ident, Row, Statement, StatementCreateIndex, StatementCreateTable, Value};
+
+
+/***** HELPERS *****/
+/// Reads a textual value from a [`Row`] returned by an introspection query.
+///
+/// # Arguments
+/// - `row`: The [`Row`] to read from.
+/// - `i`: The index of the column to read.
+///
+/// # Returns
+/// The text, or [`None`] if the value is not textual (e.g., NULL).
+#[inline]
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub(crate) fn row_str(row: &Row, i: usize) -> Option<&str> {
+    match row.values.get(i) {
+        Some(Value::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
+/// Reads an integer value from a [`Row`] returned by an introspection query.
+///
+/// # Arguments
+/// - `row`: The [`Row`] to read from.
+/// - `i`: The index of the column to read.
+///
+/// # Returns
+/// The integer, or [`None`] if the value is not an integer (or a string containing one).
+#[inline]
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub(crate) fn row_int(row: &Row, i: usize) -> Option<i64> {
+    match row.values.get(i) {
+        Some(Value::BigInt(i)) => Some(*i),
+        Some(Value::BigIntUnsigned(i)) => i64::try_from(*i).ok(),
+        Some(Value::String(s)) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Builds a [`ColumnDef`] from the raw properties reported by a backend.
+///
+/// # Arguments
+/// - `table`: The name of the table the column is in. Only used for logging.
+/// - `name`: The name of the column.
+/// - `raw_ty`: The type of the column as reported by the backend. Types that cannot be parsed (see [`Type::from_str()`]) are read
+///   as [`Type::Text`].
+/// - `not_null`: Whether the column cannot be NULL.
+/// - `raw_default`: The default value of the column as an SQL literal, if any. Defaults that do not fit `ty` are dropped.
+///
+/// # Returns
+/// A new [`ColumnDef`] that does not auto-increment.
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub(crate) fn introspect_column(table: &Ident, name: Ident, raw_ty: &str, not_null: bool, raw_default: Option<&str>) -> ColumnDef {
+    let ty: Type = match raw_ty.parse() {
+        Ok(ty) => ty,
+        Err(err) => {
+            warn!("Reading column '{name}' in table '{table}' as TEXT: {err}");
+            Type::Text
+        },
+    };
+    let default: Option<Value> = match raw_default.map(parse_literal) {
+        Some(Value::Null) | None => None,
+        Some(value) => match value.cast(&ty) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warn!("Dropping default value of column '{name}' in table '{table}': {err}");
+                None
+            },
+        },
+    };
+    ColumnDef { name, ty, auto_increment: false, not_null, default }
+}
+
+/// Casts the values of a row to the types of their columns where possible.
+///
+/// Backends may return values more loosely typed than their columns (e.g., SQLite's TEXT dates), so this restores those. Values that
+/// cannot be cast are kept as-is.
+///
+/// # Arguments
+/// - `values`: The values to cast, e.g., those of a [`Row`].
+/// - `cols`: The definitions of the columns the values are in, in the same order.
+///
+/// # Returns
+/// The values, cast where possible.
+pub(crate) fn cast_to_columns(values: Vec<Value>, cols: &[ColumnDef]) -> Vec<Value> {
+    values.into_iter().zip(cols).map(|(value, col)| value.cast(&col.ty).unwrap_or(value)).collect()
+}
+
+/// Orders items such that those of tables referred to by foreign keys come first.
+///
+/// # Arguments
+/// - `items`: The items to order, e.g., table definitions or rows to insert. Items of the same table keep their relative order.
+/// - `table`: A closure that returns the definition of the table an item belongs to.
+///
+/// # Returns
+/// A tuple of the ordered items, and those that could not be ordered because the foreign keys between their tables form a cycle (in
+/// their original order).
+pub(crate) fn order_by_foreign_keys<T>(mut items: Vec<T>, table: impl Fn(&T) -> &StatementCreateTable) -> (Vec<T>, Vec<T>) {
+    let mut ordered: Vec<T> = Vec::with_capacity(items.len());
+    while let Some(next) = items.iter().position(|item| {
+        let def: &StatementCreateTable = table(item);
+        def.foreign_keys.iter().all(|fk| fk.table == def.name || items.iter().all(|other| table(other).name != fk.table))
+    }) {
+        ordered.push(items.remove(next));
+    }
+    (ordered, items)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Something that can execute SQL [`Statement`]s, such as a [`Database`] or a transaction on one.
+pub trait Executor {
+    /// The type of errors returned by this executor.
+    type Error: 'static + Send + Sync + Error;
+    /// The SQL dialect spoken by this executor.
+    const DIALECT: Dialect;
+
+
+    /// Executes the given SQL [`Statement`] on the backend.
+    ///
+    /// Note that the query is serialized as-is. To use a prepared statement, see `Self::execute_prepared()`.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` for some reason.
+    fn execute(&mut self, stmt: &Statement) -> Result<ExecuteResult, Self::Error>;
+
+    /// Executes the given SQL [`Statement`] on the backend as a prepared statement.
+    ///
+    /// Any values in the statement are bound as parameters instead of serialized as literals.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] describing what the statement did.
+    ///
+    /// # Errors
+    /// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+    fn execute_prepared(&mut self, stmt: &Statement) -> Result<ExecuteResult, Self::Error>;
+
+    /// Executes the given SQL [`Statement`] on the backend and returns the resulting rows.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results.
+    fn query(&mut self, stmt: &Statement) -> Result<Vec<Row>, Self::Error>;
+
+    /// Executes the given SQL [`Statement`] on the backend and calls the given closure for every resulting row as it is read.
+    ///
+    /// Unlike `Self::query()`, this doesn't keep all rows in memory.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    /// - `func`: The closure to call for every [`Row`]. If it errors, no more rows are read.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results, or if `func` errors.
+    fn for_each_row<E>(&mut self, stmt: &Statement, func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+    where
+        E: From<Self::Error>;
+
+    /// Executes a raw SQL query on the backend, discarding any results.
+    ///
+    /// This is an escape hatch for statements that cannot be expressed as a [`Statement`], such as those read from a dump (see
+    /// [`restore()`](crate::dump::restore)). The query is sent as-is, so never build it from untrusted input.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute, in the backend's `Self::DIALECT`.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `query` for some reason.
+    fn execute_raw(&mut self, query: &str) -> Result<(), Self::Error>;
+
+    /// Returns the names of all tables in the database, in alphabetical order.
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the backend for them.
+    fn tables(&mut self) -> Result<Vec<Ident>, Self::Error>;
+
+    /// Reads the definition of a table back from the database.
+    ///
+    /// Types are mapped back as well as the backend allows; e.g., SQLite stores most types as `TEXT`, so these are read as
+    /// [`crate::sql::Type::Text`].
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to describe.
+    ///
+    /// # Returns
+    /// A [`StatementCreateTable`] that creates the table (without its indices; see `Self::indexes()`).
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the backend, if the table doesn't exist or if it cannot be described (e.g., because
+    /// it has a composite primary key).
+    fn describe_table(&mut self, table: &Ident) -> Result<StatementCreateTable, Self::Error>;
+
+    /// Reads the indices on a table back from the database.
+    ///
+    /// The index backing the primary key is omitted, as it is part of the table's definition.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to read the indices of.
+    ///
+    /// # Returns
+    /// A list of [`StatementCreateIndex`]es that create the indices.
+    ///
+    /// # Errors
+    /// This function errors if we failed to query the backend for them.
+    fn indexes(&mut self, table: &Ident) -> Result<Vec<StatementCreateIndex>, Self::Error>;
+}
+
+
+
+/// Defines a database backend.
+pub trait Database: Executor {
+    /// The type of transactions on this database.
+    type Transaction<'t>: Executor<Error = Self::Error>
+    where
+        Self: 't;
+
+
+    /// Runs the given closure in a transaction.
+    ///
+    /// The transaction is committed if the closure returns [`Ok`], and rolled back if it returns [`Err`].
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`Self::Transaction`] to execute statements on.
+    ///
+    /// # Returns
+    /// The value returned by `func`.
+    ///
+    /// # Errors
+    /// This function errors if `func` errors, or if we failed to begin or commit the transaction.
+    fn transaction<'s, T, E>(&'s self, func: impl FnOnce(&mut Self::Transaction<'s>) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<Self::Error>;
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    27 Dec 2023, 11:33:39
 //  Last edited:
-//    30 Dec 2023, 13:02:55
+//    18 Oct 2026, 14:35:04
 //  Auto updated?
 //    Yes
 //
@@ -13,21 +13,182 @@
 //!   type-safe and all that.
 //
 
+use std::cell::RefCell;
 use std::fmt::{Display, Formatter, Result as FResult};
+use std::str::FromStr;
+use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset as _, TimeZone, Timelike as _};
 use enum_debug::EnumDebug;
+use rust_decimal::prelude::{FromPrimitive as _, ToPrimitive as _};
+use rust_decimal::Decimal;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+
+/***** ERRORS *****/
+pub mod errors {
+    use std::error::Error;
+    use std::fmt::{Display, Formatter, Result as FResult};
+
+    use super::{Type, Value};
+
+    /// Failed to parse a [`Type`](super::Type) from a string.
+    #[derive(Debug)]
+    pub enum TypeParseError {
+        /// We didn't recognize the type identifier given.
+        UnknownIdentifier { raw: String },
+        /// We didn't recognize a modifier (e.g., `UNSIGNED`) of the type.
+        UnknownModifier { raw: String, modifier: String },
+        /// An argument of the type (e.g., the `255` in `VARCHAR(255)`) was missing or not valid.
+        IllegalArguments { raw: String },
+        /// The parenthesis containing the type's arguments was not closed.
+        UnclosedParenthesis { raw: String },
+    }
+    impl Display for TypeParseError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+            use TypeParseError::*;
+            match self {
+                UnknownIdentifier { raw } => write!(f, "Unknown SQL column type '{raw}'"),
+                UnknownModifier { raw, modifier } => write!(f, "Unknown modifier '{modifier}' in SQL column type '{raw}'"),
+                IllegalArguments { raw } => write!(f, "Illegal arguments in SQL column type '{raw}'"),
+                UnclosedParenthesis { raw } => write!(f, "Unclosed parenthesis in SQL column type '{raw}'"),
+            }
+        }
+    }
+    impl Error for TypeParseError {}
+
+    /// Failed to cast a [`Value`] to a particular [`Type`].
+    #[derive(Debug)]
+    pub enum ValueCastError {
+        /// The value is of a kind that cannot be cast to the given type at all.
+        Incompatible { from: Type, to: Type },
+        /// The value is of a compatible kind, but this particular value does not fit the given type.
+        IllegalValue { value: Value, to: Type },
+    }
+    impl Display for ValueCastError {
+        #[inline]
+        fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+            use ValueCastError::*;
+            match self {
+                Incompatible { from, to } => write!(f, "Cannot cast a value of type {from} to type {to}"),
+                IllegalValue { value, to } => write!(f, "Cannot cast value {value:?} to type {to}"),
+            }
+        }
+    }
+    impl Error for ValueCastError {}
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The format in which we write date literals.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+/// The format in which we write time literals. Only writes as many fractional digits as necessary.
+pub const TIME_FORMAT: &str = "%H:%M:%S%.f";
+/// The format in which we write (naive) datetime literals. Only writes as many fractional digits as necessary.
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+/// The format in which we write zoned datetime literals. Only writes as many fractional digits as necessary.
+pub const DATETIME_TZ_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%:z";
+
+
+/// The (uppercase) keywords that are reserved in either MySQL or SQLite, and therefore need quoting when used as an identifier.
+///
+/// Must be kept sorted, as we binary search it.
+const RESERVED_WORDS: &[&str] = &[
+    "ABORT", "ACTION", "ADD", "AFTER", "ALL", "ALTER", "ALWAYS", "ANALYZE", "AND", "AS", "ASC", "ATTACH", "AUTOINCREMENT", "AUTO_INCREMENT",
+    "BEFORE", "BEGIN", "BETWEEN", "BIGINT", "BINARY", "BLOB", "BOTH", "BY", "CALL", "CASCADE", "CASE", "CAST", "CHANGE", "CHAR", "CHARACTER",
+    "CHECK", "COLLATE", "COLUMN", "COMMIT", "CONDITION", "CONFLICT", "CONSTRAINT", "CONTINUE", "CONVERT", "CREATE", "CROSS", "CURRENT",
+    "CURRENT_DATE", "CURRENT_TIME", "CURRENT_TIMESTAMP", "CURRENT_USER", "CURSOR", "DATABASE", "DATABASES", "DEC", "DECIMAL", "DECLARE",
+    "DEFAULT", "DEFERRABLE", "DEFERRED", "DELETE", "DESC", "DESCRIBE", "DETACH", "DISTINCT", "DIV", "DO", "DOUBLE", "DROP", "EACH", "ELSE",
+    "ELSEIF", "END", "ENUM", "ESCAPE", "EXCEPT", "EXCLUDE", "EXCLUSIVE", "EXISTS", "EXPLAIN", "FAIL", "FALSE", "FETCH", "FILTER", "FIRST",
+    "FLOAT", "FOLLOWING", "FOR", "FORCE", "FOREIGN", "FROM", "FULL", "FULLTEXT", "GENERATED", "GLOB", "GRANT", "GROUP", "GROUPS", "HAVING",
+    "IF", "IGNORE", "IMMEDIATE", "IN", "INDEX", "INDEXED", "INITIALLY", "INNER", "INSERT", "INSTEAD", "INT", "INTEGER", "INTERSECT",
+    "INTERVAL", "INTO", "IS", "ISNULL", "JOIN", "KEY", "KEYS", "KILL", "LAST", "LEADING", "LEFT", "LIKE", "LIMIT", "LOCK", "LONG", "LOOP",
+    "MATCH", "MATERIALIZED", "MOD", "NATURAL", "NO", "NOT", "NOTHING", "NOTNULL", "NULL", "NULLS", "NUMERIC", "OF", "OFFSET", "ON", "OPTION",
+    "OR", "ORDER", "OTHERS", "OUTER", "OVER", "PARTITION", "PLAN", "PRAGMA", "PRECEDING", "PRECISION", "PRIMARY", "PROCEDURE", "QUERY",
+    "RAISE", "RANGE", "READ", "REAL", "RECURSIVE", "REFERENCES", "REGEXP", "REINDEX", "RELEASE", "RENAME", "REPEAT", "REPLACE", "REQUIRE",
+    "RESTRICT", "RETURN", "RETURNING", "REVOKE", "RIGHT", "RLIKE", "ROLLBACK", "ROW", "ROWS", "SAVEPOINT", "SCHEMA", "SELECT", "SET", "SHOW",
+    "SMALLINT", "SQL", "TABLE", "TEMP", "TEMPORARY", "THEN", "TIES", "TINYINT", "TO", "TRAILING", "TRANSACTION", "TRIGGER", "TRUE", "UNBOUNDED",
+    "UNION", "UNIQUE", "UNLOCK", "UNSIGNED", "UPDATE", "USAGE", "USE", "USING", "VACUUM", "VALUES", "VARCHAR", "VIEW", "VIRTUAL", "WHEN",
+    "WHERE", "WHILE", "WINDOW", "WITH", "WITHOUT", "WRITE", "XOR", "ZEROFILL",
+];
+
+
+
 
 
 /***** SERIALIZATION *****/
+/// Enumerates the SQL dialects we can serialize to.
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum Dialect {
+    /// The dialect spoken by MySQL (and MariaDB).
+    MySql,
+    /// The dialect spoken by SQLite.
+    Sqlite,
+}
+impl Dialect {
+    /// Returns the maximum number of `?`-placeholders that a single prepared statement may have in this dialect.
+    ///
+    /// For SQLite, this is the default of `SQLITE_MAX_VARIABLE_NUMBER` since 3.32.0.
+    ///
+    /// # Returns
+    /// The maximum number of placeholders.
+    #[inline]
+    pub const fn max_params(&self) -> usize {
+        match self {
+            Self::MySql => 65535,
+            Self::Sqlite => 32766,
+        }
+    }
+}
+
+/// Defines the context in which a [`ToSql`]-enabled type is serialized.
+#[derive(Clone, Debug)]
+pub struct SqlContext {
+    /// The dialect to serialize to.
+    pub dialect: Dialect,
+    /// If [`Some`], we serialize in parameterized mode: [`Value`]s are not written as literals but as `?`-placeholders, and collected here to
+    /// be bound separately.
+    pub params:  Option<RefCell<Vec<Value>>>,
+}
+impl SqlContext {
+    /// Constructor for an SqlContext that writes values as literals.
+    ///
+    /// # Arguments
+    /// - `dialect`: The [`Dialect`] to serialize to.
+    ///
+    /// # Returns
+    /// A new SqlContext.
+    #[inline]
+    pub fn new(dialect: Dialect) -> Self { Self { dialect, params: None } }
+
+    /// Constructor for an SqlContext that writes values as `?`-placeholders.
+    ///
+    /// # Arguments
+    /// - `dialect`: The [`Dialect`] to serialize to.
+    ///
+    /// # Returns
+    /// A new SqlContext in parameterized mode.
+    #[inline]
+    pub fn parameterized(dialect: Dialect) -> Self { Self { dialect, params: Some(RefCell::new(vec![])) } }
+}
+
 /// Formats an [`ToSql`]-enabled type to some formatter.
 pub struct ToSqlFormatter<'o, O> {
     /// The object to serialize.
     obj: &'o O,
+    /// The context in which to serialize it.
+    ctx: SqlContext,
 }
 impl<'o, O: ToSql> Display for ToSqlFormatter<'o, O> {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { self.obj.fmt_sql(f) }
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { self.obj.fmt_sql(f, &self.ctx) }
 }
 
 /// Implemented for all nodes in the SQL AST.
@@ -36,29 +197,154 @@ pub trait ToSql {
     ///
     /// # Arguments
     /// - `f`: The [`Formatter`] to which we write.
+    /// - `ctx`: The [`SqlContext`] that determines, e.g., which [`Dialect`] to write.
     ///
     /// # Errors
     /// This function may fail if we failed to write to the formatter.
-    fn fmt_sql(&self, f: &mut Formatter) -> FResult;
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult;
 }
 
 /// Allows a given [`ToSql`]-enabled type to be serialized to a formatter.
 ///
 /// # Arguments
 /// - `obj`: The [`ToSql`]-like type that will be serialized.
+/// - `dialect`: The [`Dialect`] to serialize to.
 ///
 /// # Returns
 /// A [`ToSqlFormatter`] that implements [`Display`] and that does the actual formatting.
 ///
 /// # Example
 /// ```rust
-/// use database::sql::{serialize_sql, StatementUseDatabase};
+/// use database::sql::{serialize_sql, Dialect, StatementUseDatabase};
 ///
 /// let stmt = StatementUseDatabase { name: "foo".into() };
-/// assert_eq!(serialize_sql(&stmt).to_string(), "USE foo;");
+/// assert_eq!(serialize_sql(&stmt, Dialect::MySql).to_string(), "USE foo;");
+///
+/// // Names that need it are quoted and escaped
+/// let stmt = StatementUseDatabase { name: "foo`; DROP DATABASE bar; --".into() };
+/// assert_eq!(serialize_sql(&stmt, Dialect::MySql).to_string(), "USE `foo``; DROP DATABASE bar; --`;");
+/// ```
+#[inline]
+pub fn serialize_sql<O: ToSql>(obj: &O, dialect: Dialect) -> ToSqlFormatter<'_, O> { ToSqlFormatter { obj, ctx: SqlContext::new(dialect) } }
+
+/// Serializes a given [`ToSql`]-enabled type in parameterized mode.
+///
+/// Instead of writing [`Value`]s as literals, they are written as `?`-placeholders and returned separately such that they can be bound to a
+/// prepared statement. The exception are default values in column definitions, which cannot be bound.
+///
+/// # Arguments
+/// - `obj`: The [`ToSql`]-like type that will be serialized.
+/// - `dialect`: The [`Dialect`] to serialize to.
+///
+/// # Returns
+/// A tuple of the serialized SQL and the values to bind to its placeholders, in order.
+///
+/// # Example
+/// ```rust
+/// use database::sql::{serialize_sql_params, Dialect, StatementInsert, Value};
+///
+/// let stmt = StatementInsert::new("foo", ["bar", "baz"]).add_row([Value::Int(42), Value::Blob(vec![0xDE, 0xAD])]);
+/// let (sql, params) = serialize_sql_params(&stmt, Dialect::Sqlite);
+/// assert_eq!(sql, "INSERT INTO foo (bar, baz) VALUES (?, ?);");
+/// assert_eq!(params.len(), 2);
 /// ```
+pub fn serialize_sql_params<O: ToSql>(obj: &O, dialect: Dialect) -> (String, Vec<Value>) {
+    let fmt: ToSqlFormatter<'_, O> = ToSqlFormatter { obj, ctx: SqlContext::parameterized(dialect) };
+    let sql: String = fmt.to_string();
+    (sql, fmt.ctx.params.map(RefCell::into_inner).unwrap_or_default())
+}
+
+/// Writes a string literal to the given formatter, escaping it for the given dialect.
+///
+/// # Arguments
+/// - `f`: The [`Formatter`] to which we write.
+/// - `value`: The raw string to write as a literal.
+/// - `ctx`: The [`SqlContext`] that determines how to escape.
+///
+/// # Errors
+/// This function may fail if we failed to write to the formatter.
+fn fmt_string_literal(f: &mut Formatter, value: &str, ctx: &SqlContext) -> FResult {
+    write!(f, "'")?;
+    for c in value.chars() {
+        match (c, ctx.dialect) {
+            ('\'', _) => write!(f, "''")?,
+            // MySQL interprets backslash escapes in string literals by default
+            ('\\', Dialect::MySql) => write!(f, "\\\\")?,
+            (c, _) => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "'")
+}
+
+/// Writes bytes as (uppercase) hexadecimal digits to the given formatter.
+///
+/// # Arguments
+/// - `f`: The [`Formatter`] to which we write.
+/// - `bytes`: The bytes to write.
+///
+/// # Errors
+/// This function may fail if we failed to write to the formatter.
+fn fmt_hex(f: &mut Formatter, bytes: &[u8]) -> FResult {
+    for byte in bytes {
+        write!(f, "{byte:02X}")?;
+    }
+    Ok(())
+}
+
+/// Writes a comma-separated list of identifiers to the given formatter.
+///
+/// # Arguments
+/// - `f`: The [`Formatter`] to which we write.
+/// - `idents`: The [`Ident`]s to write.
+/// - `ctx`: The [`SqlContext`] that determines how to escape.
+///
+/// # Errors
+/// This function may fail if we failed to write to the formatter.
+fn fmt_idents(f: &mut Formatter, idents: &[Ident], ctx: &SqlContext) -> FResult {
+    for (i, ident) in idents.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        ident.fmt_sql(f, ctx)?;
+    }
+    Ok(())
+}
+
+/// Clamps a fractional-second precision to what the given dialect supports.
+///
+/// # Arguments
+/// - `prec`: The number of fractional-second digits requested.
+/// - `ctx`: The [`SqlContext`] that determines the maximum.
+///
+/// # Returns
+/// The number of digits to write.
+#[inline]
+fn fmt_precision(prec: u8, ctx: &SqlContext) -> u8 {
+    match ctx.dialect {
+        Dialect::MySql => prec.min(6),
+        Dialect::Sqlite => prec,
+    }
+}
+
+/// Computes the number of fractional-second digits needed to faithfully represent the given nanoseconds.
+///
+/// # Arguments
+/// - `nanos`: The sub-second part of a time, in nanoseconds.
+///
+/// # Returns
+/// Either 0, 3, 6 or 9, matching the number of digits written by `%.f`.
 #[inline]
-pub fn serialize_sql<O: ToSql>(obj: &O) -> ToSqlFormatter<O> { ToSqlFormatter { obj } }
+fn fraction_digits(nanos: u32) -> u8 {
+    if nanos == 0 {
+        0
+    } else if nanos.is_multiple_of(1_000_000) {
+        3
+    } else if nanos.is_multiple_of(1_000) {
+        6
+    } else {
+        9
+    }
+}
 
 
 
@@ -74,6 +360,37 @@ pub enum Statement {
     /// CREATE TABLE foo (bar UNSIGNED INT, baz VARCHAR(32));
     /// ```
     CreateTable(StatementCreateTable),
+    /// Creates a new index on a table in the currently selected database.
+    ///
+    /// ```sql
+    /// CREATE INDEX foo_bar ON foo (bar);
+    /// ```
+    CreateIndex(StatementCreateIndex),
+
+    /// Selects rows from a table.
+    ///
+    /// ```sql
+    /// SELECT bar, baz FROM foo WHERE bar = 42 ORDER BY baz LIMIT 10;
+    /// ```
+    Select(StatementSelect),
+    /// Inserts new rows into a table.
+    ///
+    /// ```sql
+    /// INSERT INTO foo (bar, baz) VALUES (42, 'Hello, world!');
+    /// ```
+    Insert(StatementInsert),
+    /// Updates existing rows in a table.
+    ///
+    /// ```sql
+    /// UPDATE foo SET bar = 42 WHERE baz = 'Hello, world!';
+    /// ```
+    Update(StatementUpdate),
+    /// Deletes rows from a table.
+    ///
+    /// ```sql
+    /// DELETE FROM foo WHERE bar = 42;
+    /// ```
+    Delete(StatementDelete),
 
     /// Tells the database to use a different database.
     ///
@@ -82,12 +399,41 @@ pub enum Statement {
     /// ```
     UseDatabase(StatementUseDatabase),
 }
+impl Statement {
+    /// Returns whether executing this statement does nothing, i.e., whether it is an insert without any rows.
+    ///
+    /// Such a statement cannot be serialized to valid SQL, so the databases return an empty result for it without sending it.
+    ///
+    /// # Returns
+    /// True if it does nothing, or false otherwise.
+    #[inline]
+    pub fn is_noop(&self) -> bool { matches!(self, Self::Insert(insert) if insert.rows.is_empty()) }
+
+    /// Returns the columns returned by this statement's `RETURNING`-clause.
+    ///
+    /// # Returns
+    /// The columns, which is empty if the statement has no such clause (or cannot have one).
+    #[inline]
+    pub fn returning(&self) -> &[Ident] {
+        match self {
+            Self::Insert(insert) => &insert.returning,
+            Self::Update(update) => &update.returning,
+            Self::Delete(delete) => &delete.returning,
+            Self::CreateTable(_) | Self::CreateIndex(_) | Self::Select(_) | Self::UseDatabase(_) => &[],
+        }
+    }
+}
 impl ToSql for Statement {
     #[inline]
-    fn fmt_sql(&self, f: &mut Formatter) -> FResult {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
         match self {
-            Self::CreateTable(ct) => ct.fmt_sql(f),
-            Self::UseDatabase(ud) => ud.fmt_sql(f),
+            Self::CreateTable(ct) => ct.fmt_sql(f, ctx),
+            Self::CreateIndex(ci) => ci.fmt_sql(f, ctx),
+            Self::Select(s) => s.fmt_sql(f, ctx),
+            Self::Insert(i) => i.fmt_sql(f, ctx),
+            Self::Update(u) => u.fmt_sql(f, ctx),
+            Self::Delete(d) => d.fmt_sql(f, ctx),
+            Self::UseDatabase(ud) => ud.fmt_sql(f, ctx),
         }
     }
 }
@@ -115,17 +461,38 @@ impl From<&mut Statement> for Statement {
 /// ```sql
 /// CREATE TABLE foo (bar UNSIGNED INT, baz VARCHAR(32));
 /// ```
-#[derive(Clone, Debug)]
+///
+/// This can also be (de)serialized, e.g., as part of a [`Schema`](crate::schema::Schema):
+/// ```yaml
+/// name: foo
+/// columns:
+/// - name: id
+///   type: BIGINT UNSIGNED
+///   auto_increment: true
+/// - name: bar
+///   type: VARCHAR(32)
+///   not_null: true
+///   default: Hello, world!
+/// primary_key: id
+/// unique: [ bar ]
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StatementCreateTable {
     /// The name of the table to create.
-    pub name: String,
+    pub name: Ident,
     /// The definitions for each column in the table.
+    #[serde(rename = "columns")]
     pub cols: Vec<ColumnDef>,
 
     /// Sets the primary key for this table if given.
-    pub primary_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_key:  Option<Ident>,
     /// A list of unique columns on this table.
-    pub unique:      Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unique:       Vec<Ident>,
+    /// A list of foreign keys on this table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_keys: Vec<ForeignKey>,
 }
 impl StatementCreateTable {
     /// Constructor for a StatementCreateTable.
@@ -136,7 +503,7 @@ impl StatementCreateTable {
     /// # Returns
     /// A new StatementCreateTable instance.
     #[inline]
-    pub fn new(name: impl Into<String>) -> Self { Self { name: name.into(), cols: vec![], primary_key: None, unique: vec![] } }
+    pub fn new(name: impl Into<Ident>) -> Self { Self { name: name.into(), cols: vec![], primary_key: None, unique: vec![], foreign_keys: vec![] } }
 
     /// Changes the name of this table.
     ///
@@ -146,7 +513,7 @@ impl StatementCreateTable {
     /// # Returns
     /// Self for chaining.
     #[inline]
-    pub fn name(mut self, name: impl Into<String>) -> Self {
+    pub fn name(mut self, name: impl Into<Ident>) -> Self {
         self.name = name.into();
         self
     }
@@ -190,7 +557,7 @@ impl StatementCreateTable {
     /// # Panics
     /// This function may panic if the given column is not yet defined.
     #[inline]
-    pub fn primary_key(mut self, primary_key: Option<String>) -> Self {
+    pub fn primary_key(mut self, primary_key: Option<Ident>) -> Self {
         if let Some(primary_key) = &primary_key {
             if self.cols.iter().find(|c| &c.name == primary_key).is_none() {
                 panic!("Cannot mark unknown column '{primary_key}' as primary key");
@@ -213,22 +580,46 @@ impl StatementCreateTable {
     /// # Panics
     /// This function may panic if the given column is not yet defined.
     #[inline]
-    pub fn unique(mut self, name: impl Into<String>) -> Self {
-        let name: String = name.into();
+    pub fn unique(mut self, name: impl Into<Ident>) -> Self {
+        let name: Ident = name.into();
         if self.cols.iter().find(|c| c.name == name).is_none() {
             panic!("Cannot make unknown column '{name}' unique");
         }
         self.unique.push(name);
         self
     }
+
+    /// Adds a foreign key to this table.
+    ///
+    /// # Arguments
+    /// - `foreign_key`: The [`ForeignKey`] to add.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    ///
+    /// # Panics
+    /// This function may panic if any of the key's columns is not yet defined.
+    #[inline]
+    pub fn foreign_key(mut self, foreign_key: ForeignKey) -> Self {
+        for col in &foreign_key.cols {
+            if !self.cols.iter().any(|c| &c.name == col) {
+                panic!("Cannot use unknown column '{col}' in a foreign key");
+            }
+        }
+        self.foreign_keys.push(foreign_key);
+        self
+    }
 }
 impl ToSql for StatementCreateTable {
-    fn fmt_sql(&self, f: &mut Formatter) -> FResult {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
         // Write the statement up to the columns
-        write!(f, "CREATE TABLE \"{}\" (", self.name)?;
+        write!(f, "CREATE TABLE ")?;
+        self.name.fmt_sql(f, ctx)?;
+        write!(f, " (")?;
 
         // Serialize the columns
         let mut first: bool = true;
+        let mut rowid_key: bool = false;
         for col in &self.cols {
             // Write the separator
             if first {
@@ -237,26 +628,50 @@ impl ToSql for StatementCreateTable {
                 write!(f, ", ")?;
             }
 
+            // SQLite only auto-increments columns that alias the `ROWID`, which must be declared exactly like this
+            if ctx.dialect == Dialect::Sqlite && col.auto_increment && self.primary_key.as_ref() == Some(&col.name) {
+                col.name.fmt_sql(f, ctx)?;
+                write!(f, " INTEGER PRIMARY KEY AUTOINCREMENT")?;
+                rowid_key = true;
+                continue;
+            }
+
             // Write the column definition
-            col.fmt_sql(f)?;
+            col.fmt_sql(f, ctx)?;
         }
 
         // Mark any primary key
-        if let Some(primary_key) = &self.primary_key {
+        if let (Some(primary_key), false) = (&self.primary_key, rowid_key) {
             if first {
-                first = true;
+                first = false;
             } else {
                 write!(f, ", ")?;
             }
-            write!(f, "PRIMARY KEY (\"{primary_key}\")")?;
+            write!(f, "PRIMARY KEY (")?;
+            primary_key.fmt_sql(f, ctx)?;
+            write!(f, ")")?;
         }
 
         // Mark any unique keys
         if !self.unique.is_empty() {
-            if !first {
+            if first {
+                first = false;
+            } else {
+                write!(f, ", ")?;
+            }
+            write!(f, "UNIQUE (")?;
+            fmt_idents(f, &self.unique, ctx)?;
+            write!(f, ")")?;
+        }
+
+        // Mark any foreign keys
+        for foreign_key in &self.foreign_keys {
+            if first {
+                first = false;
+            } else {
                 write!(f, ", ")?;
             }
-            write!(f, "UNIQUE ({})", self.unique.iter().map(|u| format!("\"{u}\"")).collect::<Vec<String>>().join(", "))?;
+            foreign_key.fmt_sql(f, ctx)?;
         }
 
         // Write the closing parenthesis, end
@@ -265,18 +680,25 @@ impl ToSql for StatementCreateTable {
 }
 
 /// Describes how to define a column in statements like [`StatementCreateTable`].
-#[derive(Clone, Debug)]
+///
+/// When deserialized, the `default` is cast to the column's type (see [`Value::cast()`]).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "ColumnDefRaw")]
 pub struct ColumnDef {
     /// The name of the column.
-    pub name: String,
+    pub name: Ident,
     /// The datatype of the column.
+    #[serde(rename = "type")]
     pub ty:   Type,
 
     /// Whether this column auto-increments.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub auto_increment: bool,
     /// Whether this column can be NULL, but negated (i.e., `false` means it can be NULL, `true` means it cannot).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub not_null: bool,
     /// Whether new rows will have this column initialized to a default value or not. If so, then this fields denotes that value.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<Value>,
 }
 impl ColumnDef {
@@ -291,7 +713,7 @@ impl ColumnDef {
     /// # Returns
     /// A new [`ColumnDef`].
     #[inline]
-    pub fn new(name: impl Into<String>, ty: impl Into<Type>) -> Self {
+    pub fn new(name: impl Into<Ident>, ty: impl Into<Type>) -> Self {
         Self {
             name: name.into(),
             ty:   ty.into(),
@@ -306,11 +728,11 @@ impl ColumnDef {
     ///
     /// # Arguments
     /// - `name`: The name to change into.
-    ///.
+    ///
     /// # Returns
     /// Self for chaining.
     #[inline]
-    pub fn name(mut self, name: impl Into<String>) -> Self {
+    pub fn name(mut self, name: impl Into<Ident>) -> Self {
         self.name = name.into();
         self
     }
@@ -319,7 +741,7 @@ impl ColumnDef {
     ///
     /// # Arguments
     /// - `ty`: The type to change into.
-    ///.
+    ///
     /// # Returns
     /// Self for chaining.
     ///
@@ -390,21 +812,29 @@ impl ColumnDef {
 }
 impl ToSql for ColumnDef {
     #[inline]
-    fn fmt_sql(&self, f: &mut Formatter) -> FResult {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
         // Write the name
-        write!(f, "\"{}\" ", self.name)?;
+        self.name.fmt_sql(f, ctx)?;
+        write!(f, " ")?;
         // Write the type
-        self.ty.fmt_sql(f)?;
-        // Write any options
-        if self.auto_increment {
+        self.ty.fmt_sql(f, ctx)?;
+        // Write any options (SQLite has no separate auto-increment, see `StatementCreateTable`)
+        if self.auto_increment && ctx.dialect == Dialect::MySql {
             write!(f, " AUTO_INCREMENT")?;
         }
         if self.not_null {
             write!(f, " NOT NULL")?;
         }
         if let Some(default) = &self.default {
+            // Defaults are part of the schema, and thus cannot be bound as parameters
             write!(f, " DEFAULT ")?;
-            default.fmt_sql(f)?;
+            match (default, &self.ty, ctx.dialect) {
+                // MySQL requires the precision of the default to match that of the column
+                (Value::CurrentTimestamp, Type::DateTime(prec), Dialect::MySql) if *prec > 0 => {
+                    write!(f, "CURRENT_TIMESTAMP({})", fmt_precision(*prec, ctx))?
+                },
+                _ => default.fmt_sql(f, &SqlContext::new(ctx.dialect))?,
+            }
         }
 
         // Ok!
@@ -412,86 +842,1012 @@ impl ToSql for ColumnDef {
     }
 }
 
+/// Defines the fields of a [`ColumnDef`] as they are deserialized, before the default is checked against the type.
+#[derive(Deserialize)]
+struct ColumnDefRaw {
+    name: Ident,
+    #[serde(rename = "type")]
+    ty:   Type,
+    #[serde(default)]
+    auto_increment: bool,
+    #[serde(default)]
+    not_null: bool,
+    #[serde(default)]
+    default: Option<Value>,
+}
+impl TryFrom<ColumnDefRaw> for ColumnDef {
+    type Error = errors::ValueCastError;
 
+    #[inline]
+    fn try_from(value: ColumnDefRaw) -> Result<Self, Self::Error> {
+        // Deserialized values are untyped, so interpret the default in terms of the column
+        let default: Option<Value> = match value.default {
+            Some(default) => Some(default.cast(&value.ty)?),
+            None => None,
+        };
+        Ok(Self { name: value.name, ty: value.ty, auto_increment: value.auto_increment, not_null: value.not_null, default })
+    }
+}
 
-/// Statement for switching active databases.
+/// Describes a foreign key in a [`StatementCreateTable`].
 ///
 /// ```sql
-/// USE foo;
+/// FOREIGN KEY (bar) REFERENCES baz (quux) ON DELETE CASCADE
 /// ```
-#[derive(Clone, Debug)]
-pub struct StatementUseDatabase {
-    /// The name of the database to switch to.
-    pub name: String,
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ForeignKey {
+    /// The columns in this table that refer to another.
+    #[serde(rename = "columns")]
+    pub cols:      Vec<Ident>,
+    /// The table referred to.
+    #[serde(rename = "references")]
+    pub table:     Ident,
+    /// The columns referred to in the other table, matched in order with `cols`.
+    #[serde(rename = "referenced_columns")]
+    pub ref_cols:  Vec<Ident>,
+    /// What to do with this row if the referred row is deleted. If omitted, the database's default is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_delete: Option<ReferentialAction>,
+    /// What to do with this row if the referred row is updated. If omitted, the database's default is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_update: Option<ReferentialAction>,
 }
-impl ToSql for StatementUseDatabase {
+impl ForeignKey {
+    /// Constructor for the ForeignKey.
+    ///
+    /// # Arguments
+    /// - `cols`: The columns in this table that refer to another.
+    /// - `table`: The table referred to.
+    /// - `ref_cols`: The columns referred to in `table`, in the same order as `cols`.
+    ///
+    /// # Returns
+    /// A new ForeignKey.
+    ///
+    /// # Panics
+    /// This function panics if `cols` and `ref_cols` are not of the same length.
     #[inline]
-    fn fmt_sql(&self, f: &mut Formatter) -> FResult { write!(f, "USE {};", self.name) }
-}
-
-
-
-/// Enumerates possible data types in SQL.
-#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
-pub enum Type {
-    // Numeric types
-    /// A boolean (akin to `bool`).
-    Boolean,
-    /// Signed 64-bit integer (akin to `i64`).
-    BigInt,
-    /// Unsigned 64-bit integer (akin to `u64`).
-    BigIntUnsigned,
-    /// Signed 32-bit integer (akin to `i32`).
-    Int,
-    /// Unsigned 32-bit integer (akin to `u32`).
-    IntUnsigned,
-    /// Signed 16-bit integer (akin to `i16`).
-    SmallInt,
-    /// Unigned 16-bit integer (akin to `u16`).
-    SmallIntUnsigned,
-    /// Signed 8-bit integer (akin to `i8`).
-    TinyInt,
-    /// Unigned 8-bit integer (akin to `u8`).
-    TinyIntUnsigned,
-    /// A floating-point value with a given precision (in number of bits).
-    Float(usize),
-    /// A 64-bit floating-point value.
-    Real,
+    pub fn new(
+        cols: impl IntoIterator<Item = impl Into<Ident>>,
+        table: impl Into<Ident>,
+        ref_cols: impl IntoIterator<Item = impl Into<Ident>>,
+    ) -> Self {
+        let cols: Vec<Ident> = cols.into_iter().map(Into::into).collect();
+        let ref_cols: Vec<Ident> = ref_cols.into_iter().map(Into::into).collect();
+        if cols.len() != ref_cols.len() {
+            panic!("Cannot create foreign key of {} columns referring to {} columns", cols.len(), ref_cols.len());
+        }
+        Self { cols, table: table.into(), ref_cols, on_delete: None, on_update: None }
+    }
 
-    // String types
-    /// A fixed-length array of characters. Smaller strings are padded with blank characters.
-    Character(usize),
-    /// A fixed-length array of characters, except that it also stores strings smaller than its size.
-    VarChar(usize),
+    /// Changes what happens to rows if the referred row is deleted.
+    ///
+    /// # Arguments
+    /// - `action`: The [`ReferentialAction`] to take, or [`None`] to use the database's default.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn on_delete(mut self, action: Option<ReferentialAction>) -> Self {
+        self.on_delete = action;
+        self
+    }
 
-    // Date/time types
-    /// Defines a date-only time store.
-    Date,
-    /// Defines a time-only time store.
-    Time,
-    /// Combines date and time values into one time store.
-    DateTime,
+    /// Changes what happens to rows if the referred row is updated.
+    ///
+    /// # Arguments
+    /// - `action`: The [`ReferentialAction`] to take, or [`None`] to use the database's default.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn on_update(mut self, action: Option<ReferentialAction>) -> Self {
+        self.on_update = action;
+        self
+    }
+}
+impl ToSql for ForeignKey {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        write!(f, "FOREIGN KEY (")?;
+        fmt_idents(f, &self.cols, ctx)?;
+        write!(f, ") REFERENCES ")?;
+        self.table.fmt_sql(f, ctx)?;
+        write!(f, " (")?;
+        fmt_idents(f, &self.ref_cols, ctx)?;
+        write!(f, ")")?;
+        if let Some(action) = &self.on_delete {
+            write!(f, " ON DELETE ")?;
+            action.fmt_sql(f, ctx)?;
+        }
+        if let Some(action) = &self.on_update {
+            write!(f, " ON UPDATE ")?;
+            action.fmt_sql(f, ctx)?;
+        }
+        Ok(())
+    }
+}
 
-    // Miscellaneous
-    /// Defines a Binary Large Object of the given number of bytes that is larger than a single allowed column width thingy.
-    Blob(usize),
-    /// Defines a Character Large Object of the given number of characters that is larger than a single allowed column width thingy.
-    Clob(usize),
-    /// Empty type.
-    Null,
+/// Defines what happens to rows referring to another row through a [`ForeignKey`] when that row changes.
+#[derive(Clone, Copy, Debug, Deserialize, EnumDebug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentialAction {
+    /// Deletes or updates the referring rows along with the referred one.
+    Cascade,
+    /// Sets the referring columns to NULL.
+    SetNull,
+    /// Sets the referring columns to their default value (not supported by MySQL's InnoDB).
+    SetDefault,
+    /// Refuses to change the referred row.
+    Restrict,
+    /// Refuses to change the referred row, but only at the end of the statement.
+    NoAction,
 }
-impl Type {
-    /// Returns whether this Type is compatible with the given Type (i.e., they trivially cast to each other).
+impl ReferentialAction {
+    /// Parses a ReferentialAction from its SQL representation (e.g., `SET NULL`), as reported by SQLite and MySQL.
     ///
     /// # Arguments
-    /// - `other`: The other type to compare compatability with.
+    /// - `raw`: The SQL representation to parse. Case and surrounding whitespace are ignored.
     ///
     /// # Returns
-    /// Whether this type casts into the other.
-    pub fn compatible_with(&self, other: &Type) -> bool {
-        use Type::*;
-        match (self, other) {
-            // Booleans are castable to any numeric type
+    /// The parsed ReferentialAction, or [`None`] if it was not recognized.
+    pub fn from_sql(raw: &str) -> Option<Self> {
+        match raw.trim().to_uppercase().as_str() {
+            "CASCADE" => Some(Self::Cascade),
+            "SET NULL" => Some(Self::SetNull),
+            "SET DEFAULT" => Some(Self::SetDefault),
+            "RESTRICT" => Some(Self::Restrict),
+            "NO ACTION" => Some(Self::NoAction),
+            _ => None,
+        }
+    }
+}
+impl ToSql for ReferentialAction {
+    #[inline]
+    fn fmt_sql(&self, f: &mut Formatter, _ctx: &SqlContext) -> FResult {
+        match self {
+            Self::Cascade => write!(f, "CASCADE"),
+            Self::SetNull => write!(f, "SET NULL"),
+            Self::SetDefault => write!(f, "SET DEFAULT"),
+            Self::Restrict => write!(f, "RESTRICT"),
+            Self::NoAction => write!(f, "NO ACTION"),
+        }
+    }
+}
+
+
+
+/// Statement for creating indices.
+///
+/// ```sql
+/// CREATE UNIQUE INDEX foo_bar ON foo (bar, baz);
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatementCreateIndex {
+    /// The name of the index to create.
+    pub name:   Ident,
+    /// The table to create the index on.
+    pub table:  Ident,
+    /// The columns to index.
+    #[serde(rename = "columns")]
+    pub cols:   Vec<Ident>,
+    /// Whether the combination of the indexed columns must be unique.
+    #[serde(default)]
+    pub unique: bool,
+}
+impl StatementCreateIndex {
+    /// Constructor for a StatementCreateIndex.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the index to create.
+    /// - `table`: The table to create the index on.
+    /// - `cols`: The columns to index.
+    ///
+    /// # Returns
+    /// A new StatementCreateIndex instance that is not unique.
+    #[inline]
+    pub fn new(name: impl Into<Ident>, table: impl Into<Ident>, cols: impl IntoIterator<Item = impl Into<Ident>>) -> Self {
+        Self { name: name.into(), table: table.into(), cols: cols.into_iter().map(Into::into).collect(), unique: false }
+    }
+
+    /// Changes whether the indexed columns must be unique.
+    ///
+    /// # Arguments
+    /// - `unique`: Whether to enable this option or not.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+}
+impl ToSql for StatementCreateIndex {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        write!(f, "CREATE {}INDEX ", if self.unique { "UNIQUE " } else { "" })?;
+        self.name.fmt_sql(f, ctx)?;
+        write!(f, " ON ")?;
+        self.table.fmt_sql(f, ctx)?;
+        write!(f, " (")?;
+        fmt_idents(f, &self.cols, ctx)?;
+        write!(f, ");")
+    }
+}
+
+
+
+/// Statement for inserting new rows into a table.
+///
+/// ```sql
+/// INSERT INTO foo (bar, baz) VALUES (42, 'Hello, world!');
+/// ```
+///
+/// Executing an insert without any rows does nothing (see [`Statement::is_noop()`]).
+#[derive(Clone, Debug)]
+pub struct StatementInsert {
+    /// The name of the table to insert into.
+    pub table: Ident,
+    /// The names of the columns for which we give values.
+    pub cols:  Vec<Ident>,
+    /// The rows to insert. Every row must have as many values as there are `cols`.
+    pub rows:  Vec<Vec<Value>>,
+
+    /// The columns of the inserted rows to return. If empty, no `RETURNING`-clause is generated.
+    ///
+    /// Note that this is only supported by SQLite (3.35.0 and up); the MySQL databases refuse to run statements with it.
+    pub returning: Vec<Ident>,
+}
+impl StatementInsert {
+    /// Constructor for a StatementInsert.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to insert into.
+    /// - `cols`: The names of the columns for which we give values.
+    ///
+    /// # Returns
+    /// A new StatementInsert instance that doesn't insert any rows yet.
+    #[inline]
+    pub fn new(table: impl Into<Ident>, cols: impl IntoIterator<Item = impl Into<Ident>>) -> Self {
+        Self { table: table.into(), cols: cols.into_iter().map(Into::into).collect(), rows: vec![], returning: vec![] }
+    }
+
+    /// Adds a row to insert.
+    ///
+    /// # Arguments
+    /// - `row`: An iterator returning the [`Value`]s of the new row, in the same order as the columns.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    ///
+    /// # Panics
+    /// This function may panic if the number of values does not match the number of columns.
+    #[inline]
+    pub fn add_row(mut self, row: impl IntoIterator<Item = Value>) -> Self {
+        let row: Vec<Value> = row.into_iter().collect();
+        if row.len() != self.cols.len() {
+            panic!("Cannot add row with {} values to an insert of {} columns", row.len(), self.cols.len());
+        }
+        self.rows.push(row);
+        self
+    }
+
+    /// Sets the columns of the inserted rows that are returned by the statement.
+    ///
+    /// # Arguments
+    /// - `cols`: An iterator returning the names of the columns to return.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn returning(mut self, cols: impl IntoIterator<Item = impl Into<Ident>>) -> Self {
+        self.returning = cols.into_iter().map(Into::into).collect();
+        self
+    }
+}
+impl ToSql for StatementInsert {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        // Write the table & columns
+        write!(f, "INSERT INTO ")?;
+        self.table.fmt_sql(f, ctx)?;
+        write!(f, " (")?;
+        fmt_idents(f, &self.cols, ctx)?;
+        write!(f, ") VALUES ")?;
+
+        // Write the rows
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "(")?;
+            for (j, value) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, ", ")?;
+                }
+                value.fmt_sql(f, ctx)?;
+            }
+            write!(f, ")")?;
+        }
+
+        // Write the returning clause, if any
+        fmt_returning(f, &self.returning, ctx)?;
+        write!(f, ";")
+    }
+}
+
+/// Statement for updating existing rows in a table.
+///
+/// ```sql
+/// UPDATE foo SET bar = 42 WHERE baz = 'Hello, world!';
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatementUpdate {
+    /// The name of the table to update.
+    pub table:  Ident,
+    /// The pairs of columns and new values to set.
+    pub set:    Vec<(Ident, Value)>,
+    /// If given, only rows matching this condition are updated.
+    pub filter: Option<Condition>,
+
+    /// The columns of the updated rows to return. If empty, no `RETURNING`-clause is generated.
+    ///
+    /// Note that this is only supported by SQLite (3.35.0 and up); the MySQL databases refuse to run statements with it.
+    pub returning: Vec<Ident>,
+}
+impl StatementUpdate {
+    /// Constructor for a StatementUpdate.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to update.
+    ///
+    /// # Returns
+    /// A new StatementUpdate instance that doesn't set anything yet and matches all rows.
+    #[inline]
+    pub fn new(table: impl Into<Ident>) -> Self { Self { table: table.into(), set: vec![], filter: None, returning: vec![] } }
+
+    /// Adds a column to update.
+    ///
+    /// # Arguments
+    /// - `col`: The name of the column to update.
+    /// - `value`: The new [`Value`] of the column.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn set(mut self, col: impl Into<Ident>, value: impl Into<Value>) -> Self {
+        self.set.push((col.into(), value.into()));
+        self
+    }
+
+    /// Limits which rows are updated.
+    ///
+    /// # Arguments
+    /// - `filter`: If [`Some`], only rows matching the given [`Condition`] are updated. If [`None`], all rows are.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn filter(mut self, filter: Option<Condition>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets the columns of the updated rows that are returned by the statement.
+    ///
+    /// # Arguments
+    /// - `cols`: An iterator returning the names of the columns to return.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn returning(mut self, cols: impl IntoIterator<Item = impl Into<Ident>>) -> Self {
+        self.returning = cols.into_iter().map(Into::into).collect();
+        self
+    }
+}
+impl ToSql for StatementUpdate {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        // Write the table & new values
+        write!(f, "UPDATE ")?;
+        self.table.fmt_sql(f, ctx)?;
+        write!(f, " SET ")?;
+        for (i, (col, value)) in self.set.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            col.fmt_sql(f, ctx)?;
+            write!(f, " = ")?;
+            value.fmt_sql(f, ctx)?;
+        }
+
+        // Write the filter, if any
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE ")?;
+            filter.fmt_sql(f, ctx)?;
+        }
+
+        // Write the returning clause, if any
+        fmt_returning(f, &self.returning, ctx)?;
+        write!(f, ";")
+    }
+}
+
+/// Statement for deleting rows from a table.
+///
+/// ```sql
+/// DELETE FROM foo WHERE bar = 42;
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatementDelete {
+    /// The name of the table to delete from.
+    pub table:  Ident,
+    /// If given, only rows matching this condition are deleted.
+    pub filter: Option<Condition>,
+
+    /// The columns of the deleted rows to return. If empty, no `RETURNING`-clause is generated.
+    ///
+    /// Note that this is only supported by SQLite (3.35.0 and up); the MySQL databases refuse to run statements with it.
+    pub returning: Vec<Ident>,
+}
+impl StatementDelete {
+    /// Constructor for a StatementDelete.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to delete from.
+    ///
+    /// # Returns
+    /// A new StatementDelete instance that matches all rows.
+    #[inline]
+    pub fn new(table: impl Into<Ident>) -> Self { Self { table: table.into(), filter: None, returning: vec![] } }
+
+    /// Limits which rows are deleted.
+    ///
+    /// # Arguments
+    /// - `filter`: If [`Some`], only rows matching the given [`Condition`] are deleted. If [`None`], all rows are.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn filter(mut self, filter: Option<Condition>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets the columns of the deleted rows that are returned by the statement.
+    ///
+    /// # Arguments
+    /// - `cols`: An iterator returning the names of the columns to return.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn returning(mut self, cols: impl IntoIterator<Item = impl Into<Ident>>) -> Self {
+        self.returning = cols.into_iter().map(Into::into).collect();
+        self
+    }
+}
+impl ToSql for StatementDelete {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        // Write the table
+        write!(f, "DELETE FROM ")?;
+        self.table.fmt_sql(f, ctx)?;
+
+        // Write the filter, if any
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE ")?;
+            filter.fmt_sql(f, ctx)?;
+        }
+
+        // Write the returning clause, if any
+        fmt_returning(f, &self.returning, ctx)?;
+        write!(f, ";")
+    }
+}
+
+/// Writes a `RETURNING`-clause for the given columns, if there are any.
+///
+/// # Arguments
+/// - `f`: The [`Formatter`] to which we write.
+/// - `cols`: The columns to return.
+/// - `ctx`: The [`SqlContext`] that determines how to escape.
+///
+/// # Errors
+/// This function may fail if we failed to write to the formatter.
+fn fmt_returning(f: &mut Formatter, cols: &[Ident], ctx: &SqlContext) -> FResult {
+    if cols.is_empty() {
+        return Ok(());
+    }
+    write!(f, " RETURNING ")?;
+    fmt_idents(f, cols, ctx)
+}
+
+
+
+/// Statement for selecting rows from a table.
+///
+/// ```sql
+/// SELECT bar, baz FROM foo WHERE bar = 42 ORDER BY baz LIMIT 10;
+/// ```
+///
+/// # Example
+/// ```rust
+/// use database::sql::{serialize_sql, Condition, Dialect, Order, StatementSelect, Value};
+///
+/// let select = StatementSelect::new("foo")
+///     .cols(["bar", "baz"])
+///     .filter(Some(Condition::equals("bar", Value::BigInt(42))))
+///     .order_by("baz", Order::Descending)
+///     .limit(Some(10));
+/// assert_eq!(serialize_sql(&select, Dialect::Sqlite).to_string(), "SELECT bar, baz FROM foo WHERE bar = 42 ORDER BY baz DESC LIMIT 10;");
+/// assert_eq!(serialize_sql(&StatementSelect::new("foo").offset(Some(5)), Dialect::MySql).to_string(), "SELECT * FROM foo LIMIT 18446744073709551615 OFFSET 5;");
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatementSelect {
+    /// The name of the table to select from.
+    pub table:    Ident,
+    /// The columns to select. If empty, all columns are selected.
+    pub cols:     Vec<Ident>,
+    /// If given, only rows matching this condition are selected.
+    pub filter:   Option<Condition>,
+    /// The columns to order the rows by, in order of precedence.
+    pub order_by: Vec<(Ident, Order)>,
+    /// If given, at most this many rows are selected.
+    pub limit:    Option<u64>,
+    /// If given, this many rows are skipped before selecting any.
+    pub offset:   Option<u64>,
+}
+impl StatementSelect {
+    /// Constructor for a StatementSelect.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to select from.
+    ///
+    /// # Returns
+    /// A new StatementSelect instance that selects all columns of all rows.
+    #[inline]
+    pub fn new(table: impl Into<Ident>) -> Self {
+        Self { table: table.into(), cols: vec![], filter: None, order_by: vec![], limit: None, offset: None }
+    }
+
+    /// Sets the columns that are selected.
+    ///
+    /// # Arguments
+    /// - `cols`: An iterator returning the names of the columns to select. If it is empty, all columns are selected.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn cols(mut self, cols: impl IntoIterator<Item = impl Into<Ident>>) -> Self {
+        self.cols = cols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Limits which rows are selected.
+    ///
+    /// # Arguments
+    /// - `filter`: If [`Some`], only rows matching the given [`Condition`] are selected. If [`None`], all rows are.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn filter(mut self, filter: Option<Condition>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Adds a column to order the rows by.
+    ///
+    /// Note that this adds a column to the list, which only applies to rows that are equal in the previously added columns.
+    ///
+    /// # Arguments
+    /// - `col`: The name of the column to order by.
+    /// - `order`: Whether to order in ascending or descending [`Order`].
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn order_by(mut self, col: impl Into<Ident>, order: Order) -> Self {
+        self.order_by.push((col.into(), order));
+        self
+    }
+
+    /// Limits how many rows are selected.
+    ///
+    /// # Arguments
+    /// - `limit`: If [`Some`], at most this many rows are selected. If [`None`], all rows are.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn limit(mut self, limit: Option<u64>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Skips a number of rows before selecting any.
+    ///
+    /// # Arguments
+    /// - `offset`: If [`Some`], this many rows are skipped. If [`None`], none are.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn offset(mut self, offset: Option<u64>) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+impl ToSql for StatementSelect {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        // Write the columns and the table
+        write!(f, "SELECT ")?;
+        if self.cols.is_empty() {
+            write!(f, "*")?;
+        } else {
+            fmt_idents(f, &self.cols, ctx)?;
+        }
+        write!(f, " FROM ")?;
+        self.table.fmt_sql(f, ctx)?;
+
+        // Write the filter, if any
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE ")?;
+            filter.fmt_sql(f, ctx)?;
+        }
+
+        // Write the order, if any
+        for (i, (col, order)) in self.order_by.iter().enumerate() {
+            write!(f, "{}", if i == 0 { " ORDER BY " } else { ", " })?;
+            col.fmt_sql(f, ctx)?;
+            if *order == Order::Descending {
+                write!(f, " DESC")?;
+            }
+        }
+
+        // Write the limit and offset, if any (both dialects only accept an offset after a limit)
+        match (self.limit, self.offset, ctx.dialect) {
+            (Some(limit), _, _) => write!(f, " LIMIT {limit}")?,
+            (None, Some(_), Dialect::MySql) => write!(f, " LIMIT {}", u64::MAX)?,
+            (None, Some(_), Dialect::Sqlite) => write!(f, " LIMIT -1")?,
+            (None, None, _) => {},
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " OFFSET {offset}")?;
+        }
+        write!(f, ";")
+    }
+}
+
+/// Defines the direction in which rows are ordered by a [`StatementSelect`].
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum Order {
+    /// From small to large.
+    Ascending,
+    /// From large to small.
+    Descending,
+}
+
+/// Describes a condition used to select rows in, e.g., the `WHERE`-clause of a [`StatementUpdate`].
+#[derive(Clone, Debug, EnumDebug)]
+pub enum Condition {
+    /// Compares a column to a value.
+    Compare { col: Ident, op: Operator, value: Value },
+    /// Checks whether a column is NULL.
+    IsNull(Ident),
+    /// Matches if all nested conditions match. If there are none, always matches.
+    And(Vec<Condition>),
+    /// Matches if any nested condition matches. If there are none, never matches.
+    Or(Vec<Condition>),
+    /// Matches if the nested condition does not.
+    Not(Box<Condition>),
+}
+impl Condition {
+    /// Convenience constructor for a [`Condition::Compare`] that checks for equality.
+    ///
+    /// # Arguments
+    /// - `col`: The name of the column to compare.
+    /// - `value`: The [`Value`] to compare with.
+    ///
+    /// # Returns
+    /// A new [`Condition`].
+    #[inline]
+    pub fn equals(col: impl Into<Ident>, value: impl Into<Value>) -> Self { Self::Compare { col: col.into(), op: Operator::Eq, value: value.into() } }
+}
+impl ToSql for Condition {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        match self {
+            Self::Compare { col, op, value } => {
+                col.fmt_sql(f, ctx)?;
+                write!(f, " ")?;
+                op.fmt_sql(f, ctx)?;
+                write!(f, " ")?;
+                value.fmt_sql(f, ctx)
+            },
+            Self::IsNull(col) => {
+                col.fmt_sql(f, ctx)?;
+                write!(f, " IS NULL")
+            },
+            Self::And(conds) | Self::Or(conds) => {
+                if conds.is_empty() {
+                    return write!(f, "{}", if matches!(self, Self::And(_)) { '1' } else { '0' });
+                }
+                for (i, cond) in conds.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if matches!(self, Self::And(_)) { " AND " } else { " OR " })?;
+                    }
+                    write!(f, "(")?;
+                    cond.fmt_sql(f, ctx)?;
+                    write!(f, ")")?;
+                }
+                Ok(())
+            },
+            Self::Not(cond) => {
+                write!(f, "NOT (")?;
+                cond.fmt_sql(f, ctx)?;
+                write!(f, ")")
+            },
+        }
+    }
+}
+
+/// Enumerates the possible comparison operators in a [`Condition`].
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum Operator {
+    /// `=`
+    Eq,
+    /// `<>`
+    NotEq,
+    /// `<`
+    Less,
+    /// `<=`
+    LessEq,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEq,
+}
+impl ToSql for Operator {
+    #[inline]
+    fn fmt_sql(&self, f: &mut Formatter, _ctx: &SqlContext) -> FResult {
+        match self {
+            Self::Eq => write!(f, "="),
+            Self::NotEq => write!(f, "<>"),
+            Self::Less => write!(f, "<"),
+            Self::LessEq => write!(f, "<="),
+            Self::Greater => write!(f, ">"),
+            Self::GreaterEq => write!(f, ">="),
+        }
+    }
+}
+
+
+
+/// Statement for switching active databases.
+///
+/// ```sql
+/// USE foo;
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatementUseDatabase {
+    /// The name of the database to switch to.
+    pub name: Ident,
+}
+impl ToSql for StatementUseDatabase {
+    #[inline]
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        write!(f, "USE ")?;
+        self.name.fmt_sql(f, ctx)?;
+        write!(f, ";")
+    }
+}
+
+
+
+/// Represents an identifier (e.g., the name of a table or column) in SQL.
+///
+/// Identifiers are only quoted when necessary, i.e., when they contain characters other than alphanumerics and underscores or when they are a
+/// reserved word. Any quote characters in the identifier are properly escaped.
+///
+/// # Example
+/// ```rust
+/// use database::sql::{serialize_sql, Dialect, Ident};
+///
+/// assert_eq!(serialize_sql(&Ident::from("foo_bar"), Dialect::Sqlite).to_string(), "foo_bar");
+/// assert_eq!(serialize_sql(&Ident::from("order"), Dialect::Sqlite).to_string(), "\"order\"");
+/// assert_eq!(serialize_sql(&Ident::from("order"), Dialect::MySql).to_string(), "`order`");
+/// assert_eq!(serialize_sql(&Ident::from("a\"b`c"), Dialect::Sqlite).to_string(), "\"a\"\"b`c\"");
+/// assert_eq!(serialize_sql(&Ident::from("a\"b`c"), Dialect::MySql).to_string(), "`a\"b``c`");
+/// assert_eq!(serialize_sql(&Ident::from("x\"); DROP TABLE y; --"), Dialect::Sqlite).to_string(), "\"x\"\"); DROP TABLE y; --\"");
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(transparent)]
+pub struct Ident(String);
+impl Ident {
+    /// Constructor for the Ident.
+    ///
+    /// # Arguments
+    /// - `name`: The (unescaped) name of the identifier.
+    ///
+    /// # Returns
+    /// A new Ident.
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self { Self(name.into()) }
+
+    /// Returns whether this identifier is a reserved word in either MySQL or SQLite.
+    ///
+    /// # Returns
+    /// True if it is, or false otherwise.
+    #[inline]
+    pub fn is_reserved(&self) -> bool { RESERVED_WORDS.binary_search(&self.0.to_uppercase().as_str()).is_ok() }
+
+    /// Returns whether this identifier must be quoted to be used in a query.
+    ///
+    /// # Returns
+    /// True if it contains characters other than ASCII alphanumerics or underscores, starts with a digit, is empty or is a reserved word.
+    pub fn needs_quoting(&self) -> bool {
+        match self.0.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {},
+            _ => return true,
+        }
+        !self.0.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') || self.is_reserved()
+    }
+
+    /// Returns the (unescaped) name of this identifier.
+    ///
+    /// # Returns
+    /// A reference to the internal name.
+    #[inline]
+    pub fn as_str(&self) -> &str { &self.0 }
+}
+impl ToSql for Ident {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
+        if !self.needs_quoting() {
+            return write!(f, "{}", self.0);
+        }
+
+        // Quote it, doubling any embedded quote characters
+        let quote: char = match ctx.dialect {
+            Dialect::MySql => '`',
+            Dialect::Sqlite => '"',
+        };
+        write!(f, "{quote}")?;
+        for c in self.0.chars() {
+            if c == quote {
+                write!(f, "{quote}")?;
+            }
+            write!(f, "{c}")?;
+        }
+        write!(f, "{quote}")
+    }
+}
+impl Display for Ident {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "{}", self.0) }
+}
+impl AsRef<str> for Ident {
+    #[inline]
+    fn as_ref(&self) -> &str { &self.0 }
+}
+impl PartialEq<str> for Ident {
+    #[inline]
+    fn eq(&self, other: &str) -> bool { self.0 == other }
+}
+impl PartialEq<&str> for Ident {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool { self.0 == *other }
+}
+impl From<&str> for Ident {
+    #[inline]
+    fn from(value: &str) -> Self { Self(value.into()) }
+}
+impl From<String> for Ident {
+    #[inline]
+    fn from(value: String) -> Self { Self(value) }
+}
+impl From<&String> for Ident {
+    #[inline]
+    fn from(value: &String) -> Self { Self(value.clone()) }
+}
+impl From<&Ident> for Ident {
+    #[inline]
+    fn from(value: &Self) -> Self { value.clone() }
+}
+impl From<Ident> for String {
+    #[inline]
+    fn from(value: Ident) -> Self { value.0 }
+}
+
+
+
+/// Enumerates possible data types in SQL.
+#[derive(Clone, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum Type {
+    // Numeric types
+    /// A boolean (akin to `bool`).
+    Boolean,
+    /// Signed 64-bit integer (akin to `i64`).
+    BigInt,
+    /// Unsigned 64-bit integer (akin to `u64`).
+    BigIntUnsigned,
+    /// Signed 32-bit integer (akin to `i32`).
+    Int,
+    /// Unsigned 32-bit integer (akin to `u32`).
+    IntUnsigned,
+    /// Signed 16-bit integer (akin to `i16`).
+    SmallInt,
+    /// Unigned 16-bit integer (akin to `u16`).
+    SmallIntUnsigned,
+    /// Signed 8-bit integer (akin to `i8`).
+    TinyInt,
+    /// Unigned 8-bit integer (akin to `u8`).
+    TinyIntUnsigned,
+    /// A floating-point value with a given precision (in number of bits).
+    Float(usize),
+    /// A 64-bit floating-point value.
+    Real,
+    /// An exact fixed-point number with the given precision (total number of digits) and scale (number of digits after the decimal point).
+    ///
+    /// Serialized as `TEXT` in SQLite, as its numeric types would lose the exactness.
+    Decimal(u8, u8),
+
+    // String types
+    /// A fixed-length array of characters. Smaller strings are padded with blank characters.
+    Character(usize),
+    /// A fixed-length array of characters, except that it also stores strings smaller than its size.
+    VarChar(usize),
+    /// A string of unbounded length.
+    ///
+    /// Serialized as `TEXT` in SQLite and as `LONGTEXT` in MySQL.
+    Text,
+    /// A string that must be one of the given values.
+    ///
+    /// Serialized as `TEXT` in SQLite, which doesn't enforce the possible values.
+    Enum(Vec<String>),
+    /// A set of strings that may only contain the given values (written comma-separated).
+    ///
+    /// Serialized as `TEXT` in SQLite, which doesn't enforce the possible values.
+    Set(Vec<String>),
+
+    // Date/time types
+    /// Defines a date-only time store.
+    Date,
+    /// Defines a time-only time store with the given number of fractional-second digits.
+    ///
+    /// Note that MySQL supports at most 6 digits.
+    Time(u8),
+    /// Combines date and time values into one time store with the given number of fractional-second digits.
+    ///
+    /// Note that MySQL supports at most 6 digits.
+    DateTime(u8),
+
+    // Miscellaneous
+    /// Defines a Binary Large Object of the given number of bytes that is larger than a single allowed column width thingy.
+    Blob(usize),
+    /// Defines a Character Large Object of the given number of characters that is larger than a single allowed column width thingy.
+    ///
+    /// Serialized as `TEXT` in SQLite, and as the smallest of `TINYTEXT`, `TEXT`, `MEDIUMTEXT` or `LONGTEXT` that fits in MySQL.
+    Clob(usize),
+    /// A JSON document.
+    ///
+    /// Serialized as `TEXT` in SQLite.
+    Json,
+    /// A universally unique identifier.
+    ///
+    /// Serialized in its hyphenated form, as `TEXT` in SQLite and as `CHAR(36)` in MySQL.
+    Uuid,
+    /// Empty type.
+    Null,
+}
+impl Type {
+    /// Returns whether this Type is compatible with the given Type (i.e., they trivially cast to each other).
+    ///
+    /// # Arguments
+    /// - `other`: The other type to compare compatability with.
+    ///
+    /// # Returns
+    /// Whether this type casts into the other.
+    pub fn compatible_with(&self, other: &Type) -> bool {
+        use Type::*;
+        match (self, other) {
+            // Booleans are castable to any numeric type
             (Boolean, BigInt | BigIntUnsigned | Int | IntUnsigned | SmallInt | SmallIntUnsigned | TinyInt | TinyIntUnsigned | Float(_) | Real) => {
                 true
             },
@@ -504,14 +1860,32 @@ impl Type {
             | (Int, BigInt)
             | (IntUnsigned, BigIntUnsigned) => true,
 
-            // Ints cast to reals
+            // Ints cast to reals, and to decimals if they have enough digits before the decimal point
             (TinyInt | TinyIntUnsigned | SmallInt | SmallIntUnsigned | Int | IntUnsigned | BigInt | BigIntUnsigned, Float(_) | Real) => true,
+            (TinyInt | TinyIntUnsigned, Decimal(prec, scale)) => prec.saturating_sub(*scale) >= 3,
+            (SmallInt | SmallIntUnsigned, Decimal(prec, scale)) => prec.saturating_sub(*scale) >= 5,
+            (Int | IntUnsigned, Decimal(prec, scale)) => prec.saturating_sub(*scale) >= 10,
+            (BigInt, Decimal(prec, scale)) => prec.saturating_sub(*scale) >= 19,
+            (BigIntUnsigned, Decimal(prec, scale)) => prec.saturating_sub(*scale) >= 20,
 
-            // Strings cast to each other if their size permits
+            // Decimals cast to reals, and to other decimals if both parts fit
+            (Decimal(..), Float(_) | Real) => true,
+            (Decimal(lprec, lscale), Decimal(rprec, rscale)) => lscale <= rscale && lprec.saturating_sub(*lscale) <= rprec.saturating_sub(*rscale),
+
+            // Strings cast to each other if their size permits, and always to unbounded text
             (Character(llen) | VarChar(llen) | Clob(llen), Character(rlen) | VarChar(rlen) | Clob(rlen)) => *llen <= *rlen,
+            (Character(_) | VarChar(_) | Clob(_) | Json, Text) => true,
+            // We cannot check at the type level if strings are valid variants, so we accept them
+            (Character(_) | VarChar(_), Enum(_) | Set(_)) => true,
+            // Enums and sets are strings
+            (Enum(_) | Set(_), Text) => true,
+            // UUIDs are written as (36-character) strings
+            (Uuid, Character(len) | VarChar(len) | Clob(len)) => *len >= 36,
+            (Uuid, Text) => true,
 
-            // Dates & Times upcast to DateTimes
-            (Date | Time, DateTime) => true,
+            // Dates & Times upcast to DateTimes, and times cast to each other if their precision permits
+            (Date | Time(_), DateTime(_)) => true,
+            (Time(lprec), Time(rprec)) | (DateTime(lprec), DateTime(rprec)) => *lprec <= *rprec,
 
             // Finally, NULL always upcasts to allow columns to have NULL
             (Null, _) => true,
@@ -522,7 +1896,7 @@ impl Type {
     }
 }
 impl ToSql for Type {
-    fn fmt_sql(&self, f: &mut Formatter) -> FResult {
+    fn fmt_sql(&self, f: &mut Formatter, ctx: &SqlContext) -> FResult {
         use Type::*;
         match self {
             Boolean => write!(f, "BIT"),
@@ -536,22 +1910,361 @@ impl ToSql for Type {
             TinyIntUnsigned => write!(f, "TINYINT UNSIGNED"),
             Float(size) => write!(f, "FLOAT({size})"),
             Real => write!(f, "REAL"),
+            Decimal(prec, scale) => match ctx.dialect {
+                Dialect::MySql => write!(f, "DECIMAL({prec}, {scale})"),
+                Dialect::Sqlite => write!(f, "TEXT"),
+            },
 
             Character(len) => write!(f, "CHARACTER({len})"),
             VarChar(len) => write!(f, "VARCHAR({len})"),
+            Text => match ctx.dialect {
+                Dialect::MySql => write!(f, "LONGTEXT"),
+                Dialect::Sqlite => write!(f, "TEXT"),
+            },
+            Enum(variants) | Set(variants) => match ctx.dialect {
+                Dialect::MySql => {
+                    write!(f, "{}(", if matches!(self, Enum(_)) { "ENUM" } else { "SET" })?;
+                    for (i, variant) in variants.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        fmt_string_literal(f, variant, ctx)?;
+                    }
+                    write!(f, ")")
+                },
+                Dialect::Sqlite => write!(f, "TEXT"),
+            },
+
+            Date => write!(f, "DATE"),
+            Time(0) => write!(f, "TIME"),
+            Time(prec) => write!(f, "TIME({})", fmt_precision(*prec, ctx)),
+            DateTime(0) => write!(f, "DATETIME"),
+            DateTime(prec) => write!(f, "DATETIME({})", fmt_precision(*prec, ctx)),
+
+            Blob(size) => write!(f, "BLOB({size})"),
+            Clob(len) => match ctx.dialect {
+                Dialect::MySql if *len <= u8::MAX as usize => write!(f, "TINYTEXT"),
+                Dialect::MySql if *len <= u16::MAX as usize => write!(f, "TEXT"),
+                Dialect::MySql if *len < (1 << 24) => write!(f, "MEDIUMTEXT"),
+                Dialect::MySql => write!(f, "LONGTEXT"),
+                Dialect::Sqlite => write!(f, "TEXT"),
+            },
+            Json => match ctx.dialect {
+                Dialect::MySql => write!(f, "JSON"),
+                Dialect::Sqlite => write!(f, "TEXT"),
+            },
+            Uuid => match ctx.dialect {
+                Dialect::MySql => write!(f, "CHAR(36)"),
+                Dialect::Sqlite => write!(f, "TEXT"),
+            },
+            Null => write!(f, "NULL"),
+        }
+    }
+}
+
+impl FromStr for Type {
+    type Err = errors::TypeParseError;
+
+    /// Parses a type from the type names reported by SQLite and MySQL.
+    ///
+    /// This accepts the names of MySQL (e.g., `INT UNSIGNED`, `VARCHAR(255)`, `DATETIME(6)` or `ENUM('a','b')`), where `TINYINT(1)` is
+    /// interpreted as a [`Type::Boolean`]. For SQLite, which accepts any type name, known names are parsed as usual and others by the
+    /// rules of SQLite's type affinity (e.g., anything containing `INT` is an integer). Note that SQLite's `INTEGER` is 64 bits, and that a
+    /// bare `NUMERIC` is read as a [`Type::Real`] as SQLite stores it (a bare `DECIMAL` is MySQL's `DECIMAL(10,0)`). `BIT(n)` is read as
+    /// a [`Type::Blob`] of the bytes needed to store `n` bits, except `BIT(1)`, which is a [`Type::Boolean`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use database::sql::Type;
+    ///
+    /// assert_eq!("int unsigned".parse::<Type>().unwrap(), Type::IntUnsigned);
+    /// assert_eq!("VARCHAR(255)".parse::<Type>().unwrap(), Type::VarChar(255));
+    /// assert_eq!("datetime(6)".parse::<Type>().unwrap(), Type::DateTime(6));
+    /// assert_eq!("tinyint(1)".parse::<Type>().unwrap(), Type::Boolean);
+    /// assert_eq!("decimal(10,2)".parse::<Type>().unwrap(), Type::Decimal(10, 2));
+    /// assert_eq!("NUMERIC".parse::<Type>().unwrap(), Type::Real);
+    /// assert_eq!("enum('a','it''s')".parse::<Type>().unwrap(), Type::Enum(vec!["a".into(), "it's".into()]));
+    /// assert_eq!("INTEGER".parse::<Type>().unwrap(), Type::BigInt);
+    /// assert_eq!("UNSIGNED BIG INT".parse::<Type>().unwrap(), Type::BigIntUnsigned);
+    /// assert_eq!("NVARCHAR(100)".parse::<Type>().unwrap(), Type::VarChar(100));
+    /// assert!("FOO".parse::<Type>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use errors::TypeParseError as Error;
+
+        // Split the type in the words outside of the parenthesis and the arguments within it
+        let (head, args, tail): (&str, Option<&str>, &str) = match s.find('(') {
+            Some(open) => match s.rfind(')') {
+                Some(close) if close > open => (&s[..open], Some(&s[open + 1..close]), &s[close + 1..]),
+                _ => return Err(Error::UnclosedParenthesis { raw: s.into() }),
+            },
+            None => (s, None, ""),
+        };
+
+        // Filter the modifiers out of the words
+        let mut unsigned: bool = false;
+        let mut words: Vec<String> = vec![];
+        for word in head.split_whitespace().chain(tail.split_whitespace()) {
+            match word.to_uppercase().as_str() {
+                "UNSIGNED" => unsigned = true,
+                // Only affects how values are displayed
+                "SIGNED" | "ZEROFILL" => {},
+                word if tail.split_whitespace().any(|t| t.eq_ignore_ascii_case(word)) => {
+                    return Err(Error::UnknownModifier { raw: s.into(), modifier: word.into() });
+                },
+                word => words.push(word.into()),
+            }
+        }
+        let name: String = words.join(" ");
+
+        // Parse the arguments as numbers
+        let nums = || -> Result<Vec<usize>, Error> {
+            match args {
+                Some(args) => args.split(',').map(|a| a.trim().parse().map_err(|_| Error::IllegalArguments { raw: s.into() })).collect(),
+                None => Ok(vec![]),
+            }
+        };
+        let size = |default: Option<usize>| -> Result<usize, Error> {
+            match (nums()?.as_slice(), default) {
+                ([size], _) => Ok(*size),
+                ([], Some(default)) => Ok(default),
+                _ => Err(Error::IllegalArguments { raw: s.into() }),
+            }
+        };
+        let precision = || -> Result<u8, Error> {
+            match nums()?.as_slice() {
+                [] => Ok(0),
+                [prec] if *prec <= 9 => Ok(*prec as u8),
+                _ => Err(Error::IllegalArguments { raw: s.into() }),
+            }
+        };
+        let int = |signed: Type, unsigned_ty: Type| -> Type { if unsigned { unsigned_ty } else { signed } };
+
+        // Now match the name
+        match name.as_str() {
+            "BOOL" | "BOOLEAN" => Ok(Self::Boolean),
+            "BIT" => match size(Some(1))? {
+                1 => Ok(Self::Boolean),
+                bits @ 2..=64 => Ok(Self::Blob(bits.div_ceil(8))),
+                _ => Err(Error::IllegalArguments { raw: s.into() }),
+            },
+            "TINYINT" if nums()? == [1] && !unsigned => Ok(Self::Boolean),
+            "TINYINT" | "INT1" => Ok(int(Self::TinyInt, Self::TinyIntUnsigned)),
+            "SMALLINT" | "INT2" | "YEAR" => Ok(int(Self::SmallInt, Self::SmallIntUnsigned)),
+            "MEDIUMINT" | "INT3" | "INT" | "INT4" => Ok(int(Self::Int, Self::IntUnsigned)),
+            "INTEGER" | "BIGINT" | "INT8" | "BIG INT" => Ok(int(Self::BigInt, Self::BigIntUnsigned)),
+            "FLOAT" => Ok(Self::Float(size(Some(32))?)),
+            "REAL" | "DOUBLE" | "DOUBLE PRECISION" | "FLOAT8" => Ok(Self::Real),
+            // Without a precision, this is SQLite's NUMERIC affinity, which stores fractions as floating-point numbers
+            "NUMERIC" if args.is_none() => Ok(Self::Real),
+            "DECIMAL" | "DEC" | "NUMERIC" | "FIXED" => match nums()?.as_slice() {
+                [] => Ok(Self::Decimal(10, 0)),
+                [prec] if *prec <= 65 => Ok(Self::Decimal(*prec as u8, 0)),
+                [prec, scale] if *prec <= 65 && scale <= prec => Ok(Self::Decimal(*prec as u8, *scale as u8)),
+                _ => Err(Error::IllegalArguments { raw: s.into() }),
+            },
+
+            "CHAR" | "CHARACTER" | "NCHAR" | "NATIVE CHARACTER" => Ok(Self::Character(size(Some(1))?)),
+            "VARCHAR" | "CHARACTER VARYING" | "VARYING CHARACTER" | "NVARCHAR" | "VARCHAR2" if args.is_some() => Ok(Self::VarChar(size(None)?)),
+            "TINYTEXT" => Ok(Self::Clob(u8::MAX as usize)),
+            "MEDIUMTEXT" => Ok(Self::Clob((1 << 24) - 1)),
+            "TEXT" | "CLOB" if args.is_some() => Ok(Self::Clob(size(None)?)),
+            "TEXT" | "CLOB" | "LONGTEXT" | "VARCHAR" | "NVARCHAR" | "STRING" => Ok(Self::Text),
+            "ENUM" | "SET" => {
+                let variants: Vec<String> = match args {
+                    Some(args) => parse_string_list(args).ok_or_else(|| Error::IllegalArguments { raw: s.into() })?,
+                    None => return Err(Error::IllegalArguments { raw: s.into() }),
+                };
+                Ok(if name == "ENUM" { Self::Enum(variants) } else { Self::Set(variants) })
+            },
+
+            "DATE" => Ok(Self::Date),
+            "TIME" => Ok(Self::Time(precision()?)),
+            "DATETIME" | "TIMESTAMP" => Ok(Self::DateTime(precision()?)),
+
+            "TINYBLOB" => Ok(Self::Blob(u8::MAX as usize)),
+            "BLOB" => Ok(Self::Blob(size(Some(u16::MAX as usize))?)),
+            "MEDIUMBLOB" => Ok(Self::Blob((1 << 24) - 1)),
+            "LONGBLOB" => Ok(Self::Blob(u32::MAX as usize)),
+            "BINARY" | "VARBINARY" => Ok(Self::Blob(size(Some(1))?)),
+            "JSON" => Ok(Self::Json),
+            "UUID" => Ok(Self::Uuid),
+            "NULL" => Ok(Self::Null),
+
+            // Otherwise, fall back to SQLite's affinity rules (see <https://www.sqlite.org/datatype3.html#determination_of_column_affinity>)
+            name if name.contains("INT") => Ok(int(Self::BigInt, Self::BigIntUnsigned)),
+            name if name.contains("CHAR") || name.contains("CLOB") || name.contains("TEXT") => Ok(Self::Text),
+            name if name.contains("BLOB") => Ok(Self::Blob(u16::MAX as usize)),
+            name if name.contains("REAL") || name.contains("FLOA") || name.contains("DOUB") => Ok(Self::Real),
+            _ => Err(Error::UnknownIdentifier { raw: s.into() }),
+        }
+    }
+}
+
+impl Display for Type {
+    /// Writes the type's canonical name, independent of any dialect.
+    ///
+    /// This name can be parsed back into the same type using [`Type::from_str()`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Type::*;
+        match self {
+            Boolean => write!(f, "BOOLEAN"),
+            BigInt => write!(f, "BIGINT"),
+            BigIntUnsigned => write!(f, "BIGINT UNSIGNED"),
+            Int => write!(f, "INT"),
+            IntUnsigned => write!(f, "INT UNSIGNED"),
+            SmallInt => write!(f, "SMALLINT"),
+            SmallIntUnsigned => write!(f, "SMALLINT UNSIGNED"),
+            TinyInt => write!(f, "TINYINT"),
+            TinyIntUnsigned => write!(f, "TINYINT UNSIGNED"),
+            Float(size) => write!(f, "FLOAT({size})"),
+            Real => write!(f, "REAL"),
+            Decimal(prec, scale) => write!(f, "DECIMAL({prec}, {scale})"),
+
+            Character(len) => write!(f, "CHAR({len})"),
+            VarChar(len) => write!(f, "VARCHAR({len})"),
+            Text => write!(f, "TEXT"),
+            Enum(variants) | Set(variants) => {
+                write!(f, "{}(", if matches!(self, Enum(_)) { "ENUM" } else { "SET" })?;
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "'{}'", variant.replace('\\', "\\\\").replace('\'', "''"))?;
+                }
+                write!(f, ")")
+            },
 
             Date => write!(f, "DATE"),
-            Time => write!(f, "TIME"),
-            DateTime => write!(f, "TIMESTAMP"),
+            Time(prec) => write!(f, "TIME({prec})"),
+            DateTime(prec) => write!(f, "DATETIME({prec})"),
 
             Blob(size) => write!(f, "BLOB({size})"),
-            Clob(len) => write!(f, "BLOB({len})"),
+            Clob(len) => write!(f, "CLOB({len})"),
+            Json => write!(f, "JSON"),
+            Uuid => write!(f, "UUID"),
             Null => write!(f, "NULL"),
         }
     }
 }
+impl Serialize for Type {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.collect_str(self) }
+}
+impl<'de> Deserialize<'de> for Type {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw: String = String::deserialize(deserializer)?;
+        raw.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Parses a literal as it appears in SQL, e.g., as the default value of a column reported by SQLite or MySQL.
+///
+/// # Arguments
+/// - `raw`: The literal to parse.
+///
+/// # Returns
+/// [`Value::Null`] for `NULL`, [`Value::CurrentTimestamp`] for `CURRENT_TIMESTAMP` (with or without precision), [`Value::Blob`] for
+/// hexadecimal literals (`X'...'`) and [`Value::String`] for anything else. Quoted strings are unquoted. Use [`Value::cast()`] to
+/// interpret the result as a particular type.
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+pub(crate) fn parse_literal(raw: &str) -> Value {
+    let raw: &str = raw.trim();
+    let upper: String = raw.to_uppercase();
+    if upper == "NULL" {
+        Value::Null
+    } else if upper.starts_with("CURRENT_TIMESTAMP") {
+        Value::CurrentTimestamp
+    } else if raw.len() >= 3 && upper.starts_with("X'") && raw.ends_with('\'') {
+        match Value::String(raw[2..raw.len() - 1].into()).cast(&Type::Blob(usize::MAX)) {
+            Ok(value) => value,
+            Err(_) => Value::String(raw.into()),
+        }
+    } else if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        match parse_string_list(raw) {
+            Some(mut values) if values.len() == 1 => Value::String(values.remove(0)),
+            _ => Value::String(raw.into()),
+        }
+    } else {
+        Value::String(raw.into())
+    }
+}
+
+/// Parses a comma-separated list of quoted strings, such as given as arguments to `ENUM` or `SET`.
+///
+/// # Arguments
+/// - `raw`: The list to parse, e.g., `'a','b'`.
+///
+/// # Returns
+/// The unquoted and unescaped strings, or [`None`] if the list was malformed.
+fn parse_string_list(raw: &str) -> Option<Vec<String>> {
+    let mut res: Vec<String> = vec![];
+    let mut chars = raw.trim().chars().peekable();
+    loop {
+        // Expect a quoted string
+        if chars.next()? != '\'' {
+            return None;
+        }
+        let mut value: String = String::new();
+        loop {
+            match chars.next()? {
+                // Quotes may be escaped by doubling them...
+                '\'' if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                    value.push('\'');
+                },
+                '\'' => break,
+                // ...or by a backslash (MySQL)
+                '\\' => value.push(chars.next()?),
+                c => value.push(c),
+            }
+        }
+        res.push(value);
+
+        // Expect a comma or the end
+        while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+        match chars.next() {
+            Some(',') => {
+                while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+                    chars.next();
+                }
+            },
+            Some(_) => return None,
+            None => return Some(res),
+        }
+    }
+}
 
 /// Enumerates possible values in SQL.
+///
+/// # Example
+/// ```rust
+/// use chrono::{FixedOffset, NaiveDate, TimeZone as _};
+/// use database::sql::{serialize_sql, Dialect, Value};
+///
+/// // Strings are escaped according to the dialect
+/// assert_eq!(serialize_sql(&Value::String("it's".into()), Dialect::Sqlite).to_string(), "'it''s'");
+/// assert_eq!(serialize_sql(&Value::String("x'); DROP TABLE y; --".into()), Dialect::Sqlite).to_string(), "'x''); DROP TABLE y; --'");
+/// assert_eq!(serialize_sql(&Value::String("x\\'); DROP TABLE y; --".into()), Dialect::MySql).to_string(), "'x\\\\''); DROP TABLE y; --'");
+///
+/// // Blobs are written as hexadecimal literals
+/// assert_eq!(serialize_sql(&Value::Blob(vec![0xDE, 0xAD, 0xBE, 0xEF]), Dialect::Sqlite).to_string(), "X'DEADBEEF'");
+///
+/// // Datetimes are quoted and keep their sub-second precision
+/// let dt = NaiveDate::from_ymd_opt(2023, 12, 30).unwrap().and_hms_micro_opt(12, 13, 1, 123456).unwrap();
+/// assert_eq!(serialize_sql(&Value::DateTime(dt), Dialect::MySql).to_string(), "'2023-12-30 12:13:01.123456'");
+/// let dt = FixedOffset::east_opt(3600).unwrap().from_local_datetime(&dt).unwrap();
+/// assert_eq!(serialize_sql(&Value::DateTimeTz(dt), Dialect::Sqlite).to_string(), "'2023-12-30 12:13:01.123456+01:00'");
+/// assert_eq!(serialize_sql(&Value::DateTimeTz(dt), Dialect::MySql).to_string(), "'2023-12-30 11:13:01.123456'");
+///
+/// // Decimals are exact, and stored as text in SQLite
+/// let dec = Value::Decimal("12.30".parse().unwrap());
+/// assert_eq!(serialize_sql(&dec, Dialect::MySql).to_string(), "12.30");
+/// assert_eq!(serialize_sql(&dec, Dialect::Sqlite).to_string(), "'12.30'");
+/// ```
 #[derive(Clone, Debug, EnumDebug)]
 pub enum Value {
     // Numeric values
@@ -577,22 +2290,36 @@ pub enum Value {
     Float(f32),
     /// A 64-bit floating-point value.
     Double(f64),
+    /// An exact fixed-point value.
+    Decimal(Decimal),
 
     // String values
-    /// String value.
+    /// String value. Also used for the values of [`Type::Enum`]s and [`Type::Set`]s.
     String(String),
 
     // Datetime values
     /// Special case of a datetime that refers to NOW.
     CurrentTimestamp,
-    /// Date and/or time value.
-    DateTime(DateTime<Utc>),
+    /// Date-only value.
+    Date(NaiveDate),
+    /// Time-only value.
+    Time(NaiveTime),
+    /// Date and time value without any timezone.
+    DateTime(NaiveDateTime),
+    /// Date and time value in a particular timezone.
+    ///
+    /// SQLite stores these with their offset. MySQL has no notion of zoned datetimes, so these are normalized to UTC before writing.
+    DateTimeTz(DateTime<FixedOffset>),
 
     // Miscellaneous values
     /// A large binary object wrapped in a `Blob`.
     Blob(Vec<u8>),
     /// A large character object wrapped in a `Clob`.
     Clob(String),
+    /// A JSON document.
+    Json(serde_json::Value),
+    /// A universally unique identifier.
+    Uuid(Uuid),
     /// Empty value.
     Null,
 }
@@ -613,23 +2340,213 @@ impl Value {
             SmallIntUnsigned(_) => Type::SmallIntUnsigned,
             TinyInt(_) => Type::TinyInt,
             TinyIntUnsigned(_) => Type::TinyIntUnsigned,
-            Float(_) => Type::Float(32),
-            Double(_) => Type::Float(64),
+            Float(_) => Type::Float(24),
+            Double(_) => Type::Float(53),
+            Decimal(d) => {
+                let scale: u8 = d.scale() as u8;
+                let digits: u8 = d.mantissa().unsigned_abs().to_string().len() as u8;
+                Type::Decimal(digits.max(scale), scale)
+            },
 
             String(s) => Type::Character(s.len()),
 
-            CurrentTimestamp => Type::DateTime,
-            DateTime(_) => Type::DateTime,
+            CurrentTimestamp => Type::DateTime(0),
+            Date(_) => Type::Date,
+            Time(t) => Type::Time(fraction_digits(t.nanosecond())),
+            DateTime(dt) => Type::DateTime(fraction_digits(dt.nanosecond())),
+            DateTimeTz(dt) => Type::DateTime(fraction_digits(dt.nanosecond())),
 
             Blob(b) => Type::Blob(b.len()),
             Clob(c) => Type::Clob(c.len()),
+            Json(_) => Type::Json,
+            Uuid(_) => Type::Uuid,
             Null => Type::Null,
         }
     }
+
+    /// Casts this value to a value of the given type.
+    ///
+    /// This is mostly useful to interpret values deserialized from files (see [`Value`]'s [`Deserialize`]-implementation), which only
+    /// knows of booleans, integers, reals, strings and JSON. As such, strings are parsed to the given type where applicable, e.g., as
+    /// decimals, dates (in [`DATE_FORMAT`]), times (in [`TIME_FORMAT`]), datetimes (in [`DATETIME_FORMAT`], [`DATETIME_TZ_FORMAT`] or
+    /// RFC 3339, or `CURRENT_TIMESTAMP`), blobs (in hexadecimal) or UUIDs.
+    ///
+    /// # Arguments
+    /// - `ty`: The [`Type`] to cast to.
+    ///
+    /// # Returns
+    /// A new [`Value`] of the given type. [`Value::Null`] is always returned as-is.
+    ///
+    /// # Errors
+    /// This function errors if this value cannot be cast to the given type, or if it does not fit it (e.g., too large or not one of an
+    /// enum's variants).
+    ///
+    /// # Example
+    /// ```rust
+    /// use database::sql::{Type, Value};
+    ///
+    /// assert!(matches!(Value::BigInt(42).cast(&Type::TinyIntUnsigned).unwrap(), Value::TinyIntUnsigned(42)));
+    /// assert!(matches!(Value::String("1.50".into()).cast(&Type::Decimal(5, 2)).unwrap(), Value::Decimal(_)));
+    /// assert!(matches!(Value::String("2023-12-30 12:13:01".into()).cast(&Type::DateTime(0)).unwrap(), Value::DateTime(_)));
+    /// assert!(matches!(Value::String("CURRENT_TIMESTAMP".into()).cast(&Type::DateTime(0)).unwrap(), Value::CurrentTimestamp));
+    /// assert!(Value::BigInt(256).cast(&Type::TinyIntUnsigned).is_err());
+    /// assert!(Value::String("c".into()).cast(&Type::Enum(vec!["a".into(), "b".into()])).is_err());
+    /// ```
+    pub fn cast(&self, ty: &Type) -> Result<Self, errors::ValueCastError> {
+        use errors::ValueCastError as Error;
+        let incompatible = || Error::Incompatible { from: self.ty(), to: ty.clone() };
+        let illegal = || Error::IllegalValue { value: self.clone(), to: ty.clone() };
+
+        // Get the value as various primitives
+        let int = || -> Result<i128, Error> {
+            match self {
+                Self::Boolean(b) => Ok(*b as i128),
+                Self::BigInt(i) => Ok(*i as i128),
+                Self::BigIntUnsigned(i) => Ok(*i as i128),
+                Self::Int(i) => Ok(*i as i128),
+                Self::IntUnsigned(i) => Ok(*i as i128),
+                Self::SmallInt(i) => Ok(*i as i128),
+                Self::SmallIntUnsigned(i) => Ok(*i as i128),
+                Self::TinyInt(i) => Ok(*i as i128),
+                Self::TinyIntUnsigned(i) => Ok(*i as i128),
+                Self::Decimal(d) if d.fract().is_zero() => d.to_i128().ok_or_else(illegal),
+                Self::String(s) => s.trim().parse().map_err(|_| illegal()),
+                _ => Err(incompatible()),
+            }
+        };
+        let real = || -> Result<f64, Error> {
+            match self {
+                Self::Float(f) => Ok(*f as f64),
+                Self::Double(d) => Ok(*d),
+                Self::Decimal(d) => d.to_f64().ok_or_else(illegal),
+                Self::String(s) => s.trim().parse().map_err(|_| illegal()),
+                _ => int().map(|i| i as f64),
+            }
+        };
+        let string = |len: Option<usize>| -> Result<&str, Error> {
+            match self {
+                Self::String(s) | Self::Clob(s) if len.map(|len| s.chars().count() <= len).unwrap_or(true) => Ok(s),
+                Self::String(_) | Self::Clob(_) => Err(illegal()),
+                _ => Err(incompatible()),
+            }
+        };
+
+        // Now cast to the target type
+        match (self, ty) {
+            (Self::Null, _) => Ok(Self::Null),
+
+            (Self::Boolean(b), Type::Boolean) => Ok(Self::Boolean(*b)),
+            (Self::String(s), Type::Boolean) => match s.trim().to_lowercase().as_str() {
+                "true" | "1" => Ok(Self::Boolean(true)),
+                "false" | "0" => Ok(Self::Boolean(false)),
+                _ => Err(illegal()),
+            },
+            (_, Type::Boolean) => match int()? {
+                0 => Ok(Self::Boolean(false)),
+                1 => Ok(Self::Boolean(true)),
+                _ => Err(illegal()),
+            },
+            (_, Type::BigInt) => int()?.try_into().map(Self::BigInt).map_err(|_| illegal()),
+            (_, Type::BigIntUnsigned) => int()?.try_into().map(Self::BigIntUnsigned).map_err(|_| illegal()),
+            (_, Type::Int) => int()?.try_into().map(Self::Int).map_err(|_| illegal()),
+            (_, Type::IntUnsigned) => int()?.try_into().map(Self::IntUnsigned).map_err(|_| illegal()),
+            (_, Type::SmallInt) => int()?.try_into().map(Self::SmallInt).map_err(|_| illegal()),
+            (_, Type::SmallIntUnsigned) => int()?.try_into().map(Self::SmallIntUnsigned).map_err(|_| illegal()),
+            (_, Type::TinyInt) => int()?.try_into().map(Self::TinyInt).map_err(|_| illegal()),
+            (_, Type::TinyIntUnsigned) => int()?.try_into().map(Self::TinyIntUnsigned).map_err(|_| illegal()),
+            // Like MySQL, treat anything of at most 24 bits as single-precision
+            (_, Type::Float(size)) if *size <= 24 => Ok(Self::Float(real()? as f32)),
+            (_, Type::Float(_) | Type::Real) => Ok(Self::Double(real()?)),
+            (_, Type::Decimal(prec, scale)) => {
+                let dec: Decimal = match self {
+                    Self::Decimal(d) => *d,
+                    Self::Float(_) | Self::Double(_) => Decimal::from_f64(real()?).ok_or_else(illegal)?,
+                    Self::String(s) => s.trim().parse().map_err(|_| illegal())?,
+                    _ => Decimal::from_i128(int()?).ok_or_else(illegal)?,
+                };
+
+                // Round to the scale of the type (as the database would), then check the remaining digits fit
+                let dec: Decimal = dec.round_dp(*scale as u32);
+                let int_digits: u32 = prec.saturating_sub(*scale) as u32;
+                if int_digits < 29 && dec.abs().trunc() >= Decimal::from_i128_with_scale(10i128.pow(int_digits), 0) {
+                    return Err(illegal());
+                }
+                Ok(Self::Decimal(dec))
+            },
+
+            (_, Type::Character(len) | Type::VarChar(len)) => Ok(Self::String(string(Some(*len))?.into())),
+            (_, Type::Text) => Ok(Self::String(string(None)?.into())),
+            (_, Type::Clob(len)) => Ok(Self::Clob(string(Some(*len))?.into())),
+            (_, Type::Enum(variants)) => {
+                let s: &str = string(None)?;
+                if variants.iter().any(|v| v == s) { Ok(Self::String(s.into())) } else { Err(illegal()) }
+            },
+            (_, Type::Set(variants)) => {
+                let s: &str = string(None)?;
+                if s.is_empty() || s.split(',').all(|s| variants.iter().any(|v| v == s)) { Ok(Self::String(s.into())) } else { Err(illegal()) }
+            },
+
+            (Self::Date(d), Type::Date) => Ok(Self::Date(*d)),
+            (Self::String(s), Type::Date) => NaiveDate::parse_from_str(s.trim(), DATE_FORMAT).map(Self::Date).map_err(|_| illegal()),
+            (Self::Time(t), Type::Time(_)) => Ok(Self::Time(*t)),
+            (Self::String(s), Type::Time(_)) => NaiveTime::parse_from_str(s.trim(), TIME_FORMAT).map(Self::Time).map_err(|_| illegal()),
+            (Self::CurrentTimestamp, Type::DateTime(_)) => Ok(Self::CurrentTimestamp),
+            (Self::Date(d), Type::DateTime(_)) => Ok(Self::DateTime(d.and_time(NaiveTime::MIN))),
+            (Self::DateTime(dt), Type::DateTime(_)) => Ok(Self::DateTime(*dt)),
+            (Self::DateTimeTz(dt), Type::DateTime(_)) => Ok(Self::DateTimeTz(*dt)),
+            (Self::String(s), Type::DateTime(_)) => {
+                let s: &str = s.trim();
+                if s.eq_ignore_ascii_case("CURRENT_TIMESTAMP") {
+                    Ok(Self::CurrentTimestamp)
+                } else if let Ok(dt) = DateTime::parse_from_str(s, DATETIME_TZ_FORMAT).or_else(|_| DateTime::parse_from_rfc3339(s)) {
+                    Ok(Self::DateTimeTz(dt))
+                } else if let Ok(dt) = NaiveDateTime::parse_from_str(s, DATETIME_FORMAT).or_else(|_| s.parse()) {
+                    Ok(Self::DateTime(dt))
+                } else {
+                    NaiveDate::parse_from_str(s, DATE_FORMAT).map(|d| Self::DateTime(d.and_time(NaiveTime::MIN))).map_err(|_| illegal())
+                }
+            },
+
+            (Self::Blob(b), Type::Blob(size)) => {
+                if b.len() <= *size {
+                    Ok(Self::Blob(b.clone()))
+                } else {
+                    Err(illegal())
+                }
+            },
+            // Strings are parsed as hexadecimal bytes
+            (Self::String(s), Type::Blob(size)) => {
+                let s: &str = s.trim();
+                if !s.is_ascii() || !s.len().is_multiple_of(2) || s.len() / 2 > *size {
+                    return Err(illegal());
+                }
+                (0..s.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .map(Self::Blob)
+                    .map_err(|_| illegal())
+            },
+            (Self::String(s) | Self::Clob(s), Type::Json) => serde_json::from_str(s).map(Self::Json).map_err(|_| illegal()),
+            (Self::Blob(_) | Self::CurrentTimestamp, Type::Json) => Err(incompatible()),
+            (_, Type::Json) => serde_json::to_value(self).map(Self::Json).map_err(|_| illegal()),
+            (Self::Uuid(u), Type::Uuid) => Ok(Self::Uuid(*u)),
+            (Self::String(s), Type::Uuid) => s.trim().parse().map(Self::Uuid).map_err(|_| illegal()),
+
+            _ => Err(incompatible()),
+        }
+    }
 }
 impl ToSql for Value {
-    fn fmt_sql(&self, fmt: &mut Formatter) -> FResult {
+    fn fmt_sql(&self, fmt: &mut Formatter, ctx: &SqlContext) -> FResult {
         use Value::*;
+
+        // In parameterized mode, bind the value instead (unless it's not really a value but an expression)
+        if let (Some(params), false) = (&ctx.params, matches!(self, CurrentTimestamp | Null)) {
+            params.borrow_mut().push(self.clone());
+            return write!(fmt, "?");
+        }
+
         match self {
             Boolean(b) => write!(fmt, "{}", if *b { '1' } else { '0' }),
             BigInt(b) => write!(fmt, "{b}"),
@@ -642,15 +2559,397 @@ impl ToSql for Value {
             TinyIntUnsigned(t) => write!(fmt, "{t}"),
             Float(f) => write!(fmt, "{f}"),
             Double(d) => write!(fmt, "{d}"),
+            Decimal(d) => match ctx.dialect {
+                Dialect::MySql => write!(fmt, "{d}"),
+                Dialect::Sqlite => write!(fmt, "'{d}'"),
+            },
 
-            String(s) => write!(fmt, "\"{s}\""),
+            String(s) => fmt_string_literal(fmt, s, ctx),
 
             CurrentTimestamp => write!(fmt, "CURRENT_TIMESTAMP"),
-            DateTime(dt) => write!(fmt, "{}", dt.format("%Y-%m-%d %H:%M:%S")),
+            Date(d) => write!(fmt, "'{}'", d.format(DATE_FORMAT)),
+            Time(t) => write!(fmt, "'{}'", t.format(TIME_FORMAT)),
+            DateTime(dt) => write!(fmt, "'{}'", dt.format(DATETIME_FORMAT)),
+            DateTimeTz(dt) => match ctx.dialect {
+                Dialect::MySql => write!(fmt, "'{}'", dt.naive_utc().format(DATETIME_FORMAT)),
+                Dialect::Sqlite => write!(fmt, "'{}'", dt.format(DATETIME_TZ_FORMAT)),
+            },
 
-            Blob(_) => todo!(),
-            Clob(c) => write!(fmt, "\"{c}\""),
+            Blob(b) => {
+                write!(fmt, "X'")?;
+                fmt_hex(fmt, b)?;
+                write!(fmt, "'")
+            },
+            Clob(c) => fmt_string_literal(fmt, c, ctx),
+            Json(j) => fmt_string_literal(fmt, &j.to_string(), ctx),
+            Uuid(u) => write!(fmt, "'{}'", u.hyphenated()),
             Null => write!(fmt, "NULL"),
         }
     }
 }
+
+
+
+impl Serialize for Value {
+    /// Serializes the value as the closest primitive of the data model.
+    ///
+    /// Decimals, dates, times, blobs (in hexadecimal) and UUIDs are written as strings (in the formats that [`Value::cast()`] parses), and
+    /// [`Value::CurrentTimestamp`] is written as the string `CURRENT_TIMESTAMP`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use Value::*;
+        match self {
+            Boolean(b) => serializer.serialize_bool(*b),
+            BigInt(i) => serializer.serialize_i64(*i),
+            BigIntUnsigned(i) => serializer.serialize_u64(*i),
+            Int(i) => serializer.serialize_i32(*i),
+            IntUnsigned(i) => serializer.serialize_u32(*i),
+            SmallInt(i) => serializer.serialize_i16(*i),
+            SmallIntUnsigned(i) => serializer.serialize_u16(*i),
+            TinyInt(i) => serializer.serialize_i8(*i),
+            TinyIntUnsigned(i) => serializer.serialize_u8(*i),
+            Float(f) => serializer.serialize_f32(*f),
+            Double(d) => serializer.serialize_f64(*d),
+            Decimal(d) => serializer.collect_str(d),
+
+            String(s) => serializer.serialize_str(s),
+
+            CurrentTimestamp => serializer.serialize_str("CURRENT_TIMESTAMP"),
+            Date(d) => serializer.collect_str(&d.format(DATE_FORMAT)),
+            Time(t) => serializer.collect_str(&t.format(TIME_FORMAT)),
+            DateTime(dt) => serializer.collect_str(&dt.format(DATETIME_FORMAT)),
+            DateTimeTz(dt) => serializer.collect_str(&dt.format(DATETIME_TZ_FORMAT)),
+
+            // Not as bytes, which most formats either don't support (YAML) or write as a list of numbers (JSON)
+            Blob(b) => {
+                /// Formats bytes as hexadecimal digits.
+                struct Hex<'b>(&'b [u8]);
+                impl Display for Hex<'_> {
+                    #[inline]
+                    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { fmt_hex(f, self.0) }
+                }
+                serializer.collect_str(&Hex(b))
+            },
+            Clob(c) => serializer.serialize_str(c),
+            Json(j) => j.serialize(serializer),
+            Uuid(u) => serializer.collect_str(&u.hyphenated()),
+            Null => serializer.serialize_none(),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Value {
+    /// Deserializes a value from the primitives of the data model.
+    ///
+    /// Booleans, integers, reals, strings and null are deserialized as [`Value::Boolean`], [`Value::BigInt`] (or [`Value::BigIntUnsigned`]
+    /// if it doesn't fit), [`Value::Double`], [`Value::String`] and [`Value::Null`], respectively. Bytes become a [`Value::Blob`], and
+    /// sequences and maps a [`Value::Json`]. Use [`Value::cast()`] to interpret the result as a particular type.
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Visitor for the [`Value`].
+        struct ValueVisitor;
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            #[inline]
+            fn expecting(&self, f: &mut Formatter) -> FResult { write!(f, "an SQL value") }
+
+            #[inline]
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> { Ok(Value::Boolean(v)) }
+
+            #[inline]
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> { Ok(Value::BigInt(v)) }
+
+            #[inline]
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(i64::try_from(v).map(Value::BigInt).unwrap_or(Value::BigIntUnsigned(v)))
+            }
+
+            #[inline]
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> { Ok(Value::Double(v)) }
+
+            #[inline]
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> { Ok(Value::String(v.into())) }
+
+            #[inline]
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> { Ok(Value::String(v)) }
+
+            #[inline]
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> { Ok(Value::Blob(v.into())) }
+
+            #[inline]
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> { Ok(Value::Blob(v)) }
+
+            #[inline]
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> { Ok(Value::Null) }
+
+            #[inline]
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> { Ok(Value::Null) }
+
+            #[inline]
+            fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> { Value::deserialize(deserializer) }
+
+            #[inline]
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                serde_json::Value::deserialize(SeqAccessDeserializer::new(seq)).map(Value::Json)
+            }
+
+            #[inline]
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                serde_json::Value::deserialize(MapAccessDeserializer::new(map)).map(Value::Json)
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl From<Decimal> for Value {
+    #[inline]
+    fn from(value: Decimal) -> Self { Self::Decimal(value) }
+}
+impl From<serde_json::Value> for Value {
+    #[inline]
+    fn from(value: serde_json::Value) -> Self { Self::Json(value) }
+}
+impl From<Uuid> for Value {
+    #[inline]
+    fn from(value: Uuid) -> Self { Self::Uuid(value) }
+}
+impl From<NaiveDate> for Value {
+    #[inline]
+    fn from(value: NaiveDate) -> Self { Self::Date(value) }
+}
+impl From<NaiveTime> for Value {
+    #[inline]
+    fn from(value: NaiveTime) -> Self { Self::Time(value) }
+}
+impl From<NaiveDateTime> for Value {
+    #[inline]
+    fn from(value: NaiveDateTime) -> Self { Self::DateTime(value) }
+}
+impl<Tz: TimeZone> From<DateTime<Tz>> for Value {
+    #[inline]
+    fn from(value: DateTime<Tz>) -> Self { Self::DateTimeTz(value.with_timezone(&value.offset().fix())) }
+}
+
+
+
+/// Represents a single row as returned by a query.
+#[derive(Clone, Debug)]
+pub struct Row {
+    /// The names of the columns in this row. Shared between all rows of the same result.
+    pub cols:   Arc<[String]>,
+    /// The values in this row, in the same order as `cols`.
+    pub values: Vec<Value>,
+}
+impl Row {
+    /// Returns the value of the column with the given name.
+    ///
+    /// # Arguments
+    /// - `col`: The name of the column to return the value of.
+    ///
+    /// # Returns
+    /// A reference to the [`Value`] in that column, or [`None`] if there is no such column.
+    #[inline]
+    pub fn get(&self, col: &str) -> Option<&Value> { self.cols.iter().position(|c| c == col).map(|i| &self.values[i]) }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes a single-row insert of the given column and value into the given table.
+    fn insert(table: &str, col: &str, value: Value, dialect: Dialect) -> String {
+        serialize_sql(&StatementInsert::new(table, [col]).add_row([value]), dialect).to_string()
+    }
+
+    #[test]
+    fn ident_quotes() {
+        assert_eq!(insert("a\"b", "c`d", Value::Null, Dialect::MySql), "INSERT INTO `a\"b` (`c``d`) VALUES (NULL);");
+        assert_eq!(insert("a\"b", "c`d", Value::Null, Dialect::Sqlite), "INSERT INTO \"a\"\"b\" (\"c`d\") VALUES (NULL);");
+        // Embedded quotes cannot end the identifier early
+        assert_eq!(
+            serialize_sql(&StatementUseDatabase { name: "x`; DROP TABLE y; --".into() }, Dialect::MySql).to_string(),
+            "USE `x``; DROP TABLE y; --`;"
+        );
+        assert_eq!(
+            serialize_sql(&StatementUseDatabase { name: "x\"; DROP TABLE y; --".into() }, Dialect::Sqlite).to_string(),
+            "USE \"x\"\"; DROP TABLE y; --\";"
+        );
+    }
+
+    #[test]
+    fn ident_needs_quoting() {
+        for (name, mysql, sqlite) in [
+            // Plain names are written as-is
+            ("foo_1", "foo_1", "foo_1"),
+            ("_foo", "_foo", "_foo"),
+            // Reserved words, regardless of case
+            ("select", "`select`", "\"select\""),
+            ("Order", "`Order`", "\"Order\""),
+            // Empty names
+            ("", "``", "\"\""),
+            // Leading digits
+            ("1foo", "`1foo`", "\"1foo\""),
+            // Non-ASCII names
+            ("naïve", "`naïve`", "\"naïve\""),
+            ("表", "`表`", "\"表\""),
+            // Whitespace and punctuation
+            ("foo bar", "`foo bar`", "\"foo bar\""),
+            ("foo.bar", "`foo.bar`", "\"foo.bar\""),
+        ] {
+            assert_eq!(insert("t", name, Value::Null, Dialect::MySql), format!("INSERT INTO t ({mysql}) VALUES (NULL);"), "{name:?}");
+            assert_eq!(insert("t", name, Value::Null, Dialect::Sqlite), format!("INSERT INTO t ({sqlite}) VALUES (NULL);"), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn string_literal_escapes() {
+        for (value, mysql, sqlite) in [
+            ("it's", "'it''s'", "'it''s'"),
+            ("''", "''''''", "''''''"),
+            ("a\\b", "'a\\\\b'", "'a\\b'"),
+            // A trailing backslash must not escape the closing quote in MySQL
+            ("a\\", "'a\\\\'", "'a\\'"),
+            ("\\'; DROP TABLE t; --", "'\\\\''; DROP TABLE t; --'", "'\\''; DROP TABLE t; --'"),
+            ("\"`", "'\"`'", "'\"`'"),
+            ("naïve 表", "'naïve 表'", "'naïve 表'"),
+        ] {
+            assert_eq!(
+                insert("t", "c", Value::String(value.into()), Dialect::MySql),
+                format!("INSERT INTO t (c) VALUES ({mysql});"),
+                "{value:?}"
+            );
+            assert_eq!(
+                insert("t", "c", Value::String(value.into()), Dialect::Sqlite),
+                format!("INSERT INTO t (c) VALUES ({sqlite});"),
+                "{value:?}"
+            );
+        }
+        // Clobs are escaped the same way
+        assert_eq!(insert("t", "c", Value::Clob("a'\\".into()), Dialect::MySql), "INSERT INTO t (c) VALUES ('a''\\\\');");
+        assert_eq!(insert("t", "c", Value::Clob("a'\\".into()), Dialect::Sqlite), "INSERT INTO t (c) VALUES ('a''\\');");
+    }
+
+    #[test]
+    fn insert_without_rows_is_noop() {
+        assert!(Statement::Insert(StatementInsert::new("t", ["c"])).is_noop());
+        assert!(!Statement::Insert(StatementInsert::new("t", ["c"]).add_row([Value::Null])).is_noop());
+        assert!(!Statement::Delete(StatementDelete::new("t")).is_noop());
+    }
+
+    /// Returns one value of every [`Value`]-variant.
+    fn values() -> Vec<Value> {
+        vec![
+            Value::Boolean(true),
+            Value::BigInt(i64::MIN),
+            Value::BigIntUnsigned(u64::MAX),
+            Value::Int(-42),
+            Value::IntUnsigned(42),
+            Value::SmallInt(i16::MIN),
+            Value::SmallIntUnsigned(u16::MAX),
+            Value::TinyInt(-128),
+            Value::TinyIntUnsigned(255),
+            Value::Float(1.5),
+            Value::Double(-0.1),
+            Value::Decimal(Decimal::new(-12345, 2)),
+            Value::String("it's a \"string\"\n".into()),
+            Value::CurrentTimestamp,
+            Value::Date(NaiveDate::from_ymd_opt(2023, 12, 30).unwrap()),
+            Value::Time(NaiveTime::from_hms_micro_opt(12, 13, 1, 500).unwrap()),
+            Value::DateTime(NaiveDate::from_ymd_opt(2023, 12, 30).unwrap().and_hms_milli_opt(12, 13, 1, 250).unwrap()),
+            Value::DateTimeTz(FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(2023, 12, 30, 12, 13, 1).unwrap()),
+            Value::Blob(vec![0xDE, 0xAD, 0x00, 0x0F]),
+            Value::Clob("lörem ipsum".into()),
+            Value::Json(serde_json::json!({ "a": [1, "two", null] })),
+            Value::Uuid(Uuid::from_u128(0x67E5_5044_10B1_426F_9247_BB68_0E5F_E0C8)),
+            Value::Null,
+        ]
+    }
+
+    /// Asserts that every value survives being serialized, deserialized and cast back to its own type.
+    fn assert_round_trip(format: &str, ser: impl Fn(&Value) -> String, de: impl Fn(&str) -> Value) {
+        for value in values() {
+            let raw: String = ser(&value);
+            let back: Value =
+                de(&raw).cast(&value.ty()).unwrap_or_else(|err| panic!("{format}: failed to cast {value:?} back from {raw:?}: {err}"));
+            assert_eq!(format!("{back:?}"), format!("{value:?}"), "{format}: {value:?} did not round-trip via {raw:?}");
+        }
+    }
+
+    #[test]
+    fn value_yaml_round_trip() {
+        assert_round_trip("YAML", |v| serde_yaml::to_string(v).unwrap(), |s| serde_yaml::from_str(s).unwrap());
+    }
+
+    #[test]
+    fn value_json_round_trip() {
+        assert_round_trip("JSON", |v| serde_json::to_string(v).unwrap(), |s| serde_json::from_str(s).unwrap());
+    }
+
+    #[test]
+    fn blob_serializes_as_hex() {
+        assert_eq!(serde_json::to_string(&Value::Blob(vec![0xDE, 0xAD])).unwrap(), "\"DEAD\"");
+    }
+
+    #[test]
+    fn type_from_str_affinity() {
+        // A bare NUMERIC is SQLite's affinity, which must not round fractions away
+        let ty: Type = "NUMERIC".parse().unwrap();
+        assert_eq!(ty, Type::Real);
+        assert_eq!(format!("{:?}", Value::Double(3.75).cast(&ty).unwrap()), format!("{:?}", Value::Double(3.75)));
+        assert_eq!(format!("{:?}", Value::String("3.75".into()).cast(&ty).unwrap()), format!("{:?}", Value::Double(3.75)));
+        assert_eq!("numeric(5,2)".parse::<Type>().unwrap(), Type::Decimal(5, 2));
+        assert_eq!("DECIMAL".parse::<Type>().unwrap(), Type::Decimal(10, 0));
+    }
+
+    #[test]
+    fn type_from_str_bits_and_booleans() {
+        assert_eq!("BIT".parse::<Type>().unwrap(), Type::Boolean);
+        assert_eq!("bit(1)".parse::<Type>().unwrap(), Type::Boolean);
+        assert_eq!("BIT(8)".parse::<Type>().unwrap(), Type::Blob(1));
+        assert_eq!("bit(9)".parse::<Type>().unwrap(), Type::Blob(2));
+        assert_eq!("BIT(64)".parse::<Type>().unwrap(), Type::Blob(8));
+        assert!("BIT(65)".parse::<Type>().is_err());
+
+        assert_eq!("TINYINT(1)".parse::<Type>().unwrap(), Type::Boolean);
+        assert_eq!("tinyint(1) unsigned".parse::<Type>().unwrap(), Type::TinyIntUnsigned);
+        assert_eq!("TINYINT(4)".parse::<Type>().unwrap(), Type::TinyInt);
+    }
+
+    #[test]
+    fn datetime_literal_round_trip() {
+        let date: NaiveDate = NaiveDate::from_ymd_opt(2023, 12, 30).unwrap();
+        let zoned: DateTime<FixedOffset> =
+            FixedOffset::west_opt(5 * 3600 + 1800).unwrap().from_local_datetime(&date.and_hms_micro_opt(23, 59, 1, 7).unwrap()).unwrap();
+        let values: [(Value, Type); 7] = [
+            (Value::Date(date), Type::Date),
+            (Value::Time(NaiveTime::from_hms_milli_opt(12, 13, 1, 250).unwrap()), Type::Time(3)),
+            (Value::Time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()), Type::Time(0)),
+            (Value::DateTime(date.and_hms_micro_opt(12, 13, 1, 123456).unwrap()), Type::DateTime(6)),
+            (Value::DateTime(date.and_hms_micro_opt(12, 13, 1, 1).unwrap()), Type::DateTime(6)),
+            (Value::DateTime(date.and_hms_milli_opt(12, 13, 1, 100).unwrap()), Type::DateTime(3)),
+            (Value::DateTimeTz(zoned), Type::DateTime(6)),
+        ];
+        for dialect in [Dialect::MySql, Dialect::Sqlite] {
+            for (value, ty) in &values {
+                assert_eq!(&value.ty(), ty, "{value:?}");
+
+                // Parse the literal back as the column would
+                let literal: String = serialize_sql(value, dialect).to_string();
+                let back: Value = Value::String(literal.trim_matches('\'').into()).cast(ty).unwrap();
+
+                // MySQL has no zoned datetimes, so these are written in UTC
+                let expected: Value = match (value, dialect) {
+                    (Value::DateTimeTz(dt), Dialect::MySql) => Value::DateTime(dt.naive_utc()),
+                    (value, _) => value.clone(),
+                };
+                assert_eq!(format!("{back:?}"), format!("{expected:?}"), "{dialect:?}: {value:?} did not round-trip via {literal}");
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    17 Dec 2023, 20:50:18
 //  Last edited:
-//    30 Dec 2023, 12:13:01
+//    18 Oct 2026, 14:35:04
 //  Auto updated?
 //    Yes
 //
@@ -13,42 +13,136 @@
 //
 
 use std::any::type_name;
+use std::collections::BTreeMap;
 use std::error;
+use std::ffi::{CStr, CString};
 use std::fmt::{Display, Formatter, Result as FResult};
+use std::os::raw::{c_char, c_int};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::ptr;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{fs, process, thread};
 
-use log::{debug, info};
+use chrono::Utc;
+use enum_debug::EnumDebug;
+use log::{debug, info, warn};
+use percent_encoding::percent_decode_str;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 pub use sqlite as backend;
-use sqlite::Connection;
+use sqlite::{Connection, OpenFlags, State};
+use sqlite3_sys as ffi;
+use url::Url;
+
+use crate::common::{load_config_file, parse_url_bool, redact_url, ExecuteResult, HealthReport, PoolStats};
+use crate::retry::{RetryPolicy, Retryable};
+use crate::schema::Schema;
+use crate::spec::{self, introspect_column, row_int, row_str};
+use crate::sql::{
+    serialize_sql, serialize_sql_params, Dialect, ForeignKey, Ident, ReferentialAction, Row, Statement, StatementCreateIndex,
+    StatementCreateTable, Value, DATETIME_FORMAT, DATETIME_TZ_FORMAT, DATE_FORMAT, TIME_FORMAT,
+};
+
+
+/***** DEFAULTS *****/
+/// Determines whether to create the database file when the user specifies nothing.
+const fn default_create() -> bool { true }
+
+/// Determines the maximum number of idle reader connections to keep when the user specifies nothing.
+const fn default_max_readers() -> usize { 4 }
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The time to wait before retrying a backup step when the source database is locked.
+const BACKUP_BUSY_SLEEP: Duration = Duration::from_millis(10);
+/// The number of times a backup step is retried when the source database is locked, before the backup fails.
+const BACKUP_BUSY_RETRIES: u32 = 500;
+/// The query used to check whether the database can be read.
+const PING_QUERY: &str = "SELECT 1;";
+/// The query used to find the version of SQLite when reporting on the health of a database.
+const HEALTH_QUERY: &str = "SELECT sqlite_version();";
+/// The extended result code for a violated `UNIQUE` constraint (`SQLITE_CONSTRAINT_UNIQUE`).
+const SQLITE_CONSTRAINT_UNIQUE: isize = 2067;
+/// The extended result code for a violated `PRIMARY KEY` constraint (`SQLITE_CONSTRAINT_PRIMARYKEY`).
+const SQLITE_CONSTRAINT_PRIMARYKEY: isize = 1555;
+
+
+
+
+
+/***** GLOBALS *****/
+/// Counts the temporary databases created by this process, such that each gets a unique file.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+
 
-use crate::common::load_config_file;
-use crate::sql::{serialize_sql, Statement};
 
 
 /***** ERRORS *****/
 /// Defines errors originating in the SQLite [`Database`]
 #[derive(Debug)]
 pub enum Error {
+    /// Failed to back up the database to the given file.
+    BackupFailed { path: PathBuf, err: sqlite::Error },
     /// Failed to load the config file.
     ConfigLoad { err: crate::common::Error },
     /// Failed to open the target database file.
     DatabaseOpen { path: PathBuf, err: sqlite::Error },
     /// The initialization code failed.
     InitFailed { path: PathBuf, err: Box<Self> },
+    /// The given connection URL is not a valid SQLite URL.
+    UrlInvalid { url: String, reason: String },
+    /// Failed to parse the given connection URL.
+    UrlParse { url: String, err: url::ParseError },
 
     /// Failed to execute the given query.
     ExecuteFailed { query: String, err: sqlite::Error },
+    /// A table could not be described.
+    IntrospectFailed { table: Ident, reason: &'static str },
+    /// Failed to retrieve the ID of the last inserted row.
+    LastInsertId { err: sqlite::Error },
+    /// Failed to run the given query or to read its results.
+    QueryFailed { query: String, err: sqlite::Error },
+    /// The schema to apply was invalid.
+    SchemaInvalid { err: crate::schema::Error },
+    /// Failed to begin a transaction.
+    TransactionBegin { err: sqlite::Error },
+    /// Failed to commit a transaction.
+    TransactionCommit { err: sqlite::Error },
+
+    /// A task offloaded to the blocking pool was cancelled (e.g., because the runtime shut down).
+    #[cfg(feature = "async")]
+    TaskCancelled { err: tokio::task::JoinError },
 }
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
         use Error::*;
         match self {
+            BackupFailed { path, .. } => write!(f, "Failed to back up SQLite database to '{}'", path.display()),
             ConfigLoad { .. } => write!(f, "Failed to load SQLite configuration file"),
             DatabaseOpen { path, .. } => write!(f, "Failed to open database file '{}'", path.display()),
             InitFailed { path, .. } => write!(f, "Failed to initialize SQLite database file '{}'", path.display()),
+            UrlInvalid { url, reason } => write!(f, "Invalid SQLite URL '{url}': {reason}"),
+            UrlParse { url, .. } => write!(f, "Failed to parse '{url}' as a URL"),
 
             ExecuteFailed { query, .. } => write!(f, "Failed to execute statement '{query}'"),
+            IntrospectFailed { table, reason } => write!(f, "Failed to describe table '{table}': {reason}"),
+            LastInsertId { .. } => write!(f, "Failed to retrieve the ID of the last inserted row"),
+            QueryFailed { query, .. } => write!(f, "Failed to query statement '{query}'"),
+            SchemaInvalid { .. } => write!(f, "Invalid schema"),
+            TransactionBegin { .. } => write!(f, "Failed to begin transaction"),
+            TransactionCommit { .. } => write!(f, "Failed to commit transaction"),
+
+            #[cfg(feature = "async")]
+            TaskCancelled { .. } => write!(f, "Blocking SQLite task was cancelled"),
         }
     }
 }
@@ -56,13 +150,81 @@ impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         use Error::*;
         match self {
+            BackupFailed { err, .. } => Some(err),
             ConfigLoad { err } => Some(err),
             DatabaseOpen { err, .. } => Some(err),
             InitFailed { err, .. } => Some(&**err),
+            UrlInvalid { .. } => None,
+            UrlParse { err, .. } => Some(err),
 
             ExecuteFailed { err, .. } => Some(err),
+            IntrospectFailed { .. } => None,
+            LastInsertId { err } => Some(err),
+            QueryFailed { err, .. } => Some(err),
+            SchemaInvalid { err } => Some(err),
+            TransactionBegin { err } => Some(err),
+            TransactionCommit { err } => Some(err),
+
+            #[cfg(feature = "async")]
+            TaskCancelled { err } => Some(err),
+        }
+    }
+}
+impl Error {
+    /// Returns the SQLite error that caused this error, if any.
+    fn cause(&self) -> Option<&sqlite::Error> {
+        use Error::*;
+        match self {
+            BackupFailed { err, .. }
+            | DatabaseOpen { err, .. }
+            | ExecuteFailed { err, .. }
+            | LastInsertId { err }
+            | QueryFailed { err, .. }
+            | TransactionBegin { err }
+            | TransactionCommit { err } => Some(err),
+            InitFailed { err, .. } => err.cause(),
+            _ => None,
+        }
+    }
+
+    /// Returns the primary result code of the SQLite error that caused this error, if any.
+    ///
+    /// Extended result codes carry the primary code in their lowest byte, so this works for both.
+    fn primary_code(&self) -> Option<c_int> { self.cause().and_then(|err| err.code).map(|code| (code & 0xff) as c_int) }
+
+    /// Returns whether this error means that the database file could not be opened.
+    pub fn is_connection(&self) -> bool {
+        match self {
+            Self::DatabaseOpen { .. } => true,
+            Self::InitFailed { err, .. } => err.is_connection(),
+            _ => self.primary_code() == Some(ffi::SQLITE_CANTOPEN),
+        }
+    }
+
+    /// Returns whether this error means that a statement violated a constraint, e.g., a unique key, foreign key or `NOT NULL`.
+    pub fn is_constraint_violation(&self) -> bool { self.primary_code() == Some(ffi::SQLITE_CONSTRAINT) }
+
+    /// Returns whether this error means that a statement violated a unique key (including a primary key).
+    pub fn is_unique_violation(&self) -> bool {
+        match self.cause() {
+            Some(sqlite::Error { code: Some(code), message }) => {
+                // Without extended result codes, SQLite only tells us which constraint failed in the message
+                *code == SQLITE_CONSTRAINT_UNIQUE
+                    || *code == SQLITE_CONSTRAINT_PRIMARYKEY
+                    || (*code == ffi::SQLITE_CONSTRAINT as isize
+                        && message.as_deref().is_some_and(|msg| msg.starts_with("UNIQUE constraint failed")))
+            },
+            _ => false,
         }
     }
+
+    /// Returns whether this error means that a table was locked by a conflicting statement on the same connection.
+    ///
+    /// Note that SQLite reports most lock conflicts between connections as a timeout instead (see [`Error::is_timeout()`]).
+    pub fn is_deadlock(&self) -> bool { self.primary_code() == Some(ffi::SQLITE_LOCKED) }
+
+    /// Returns whether this error means that the database stayed busy for longer than the configured busy timeout.
+    pub fn is_timeout(&self) -> bool { self.primary_code() == Some(ffi::SQLITE_BUSY) }
 }
 
 
@@ -70,11 +232,809 @@ impl error::Error for Error {
 
 
 /***** HELPERS *****/
+/// Parses a parameter in an SQLite connection URL.
+///
+/// # Arguments
+/// - `url`: The (redacted) URL the parameter is in, for errors.
+/// - `key`: The name of the parameter, for errors.
+/// - `value`: The value of the parameter.
+///
+/// # Returns
+/// The parsed `value`.
+///
+/// # Errors
+/// This function errors if `value` is not a valid `T`.
+fn url_param<T: FromStr>(url: &str, key: &str, value: &str) -> Result<T, Error> {
+    match value.parse() {
+        Ok(value) => Ok(value),
+        Err(_) => Err(Error::UrlInvalid { url: url.into(), reason: format!("invalid value '{value}' for parameter '{key}'") }),
+    }
+}
+
+/// Parses a parameter in an SQLite connection URL that is one of the variants of an enum, like [`JournalMode`].
+///
+/// # Arguments
+/// - `url`: The (redacted) URL the parameter is in, for errors.
+/// - `key`: The name of the parameter, for errors.
+/// - `value`: The value of the parameter, which is matched case-insensitively.
+///
+/// # Returns
+/// The parsed `value`.
+///
+/// # Errors
+/// This function errors if `value` is not one of the variants of `T`.
+fn url_param_enum<T: DeserializeOwned>(url: &str, key: &str, value: &str) -> Result<T, Error> {
+    match T::deserialize(StrDeserializer::<ValueError>::new(&value.to_lowercase())) {
+        Ok(value) => Ok(value),
+        Err(_) => Err(Error::UrlInvalid { url: url.into(), reason: format!("invalid value '{value}' for parameter '{key}'") }),
+    }
+}
+
+impl From<sqlite::Value> for Value {
+    #[inline]
+    fn from(value: sqlite::Value) -> Self {
+        match value {
+            sqlite::Value::Binary(b) => Self::Blob(b),
+            sqlite::Value::Float(f) => Self::Double(f),
+            sqlite::Value::Integer(i) => Self::BigInt(i),
+            sqlite::Value::String(s) => Self::String(s),
+            sqlite::Value::Null => Self::Null,
+        }
+    }
+}
+impl From<&Value> for sqlite::Value {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Boolean(b) => Self::Integer(*b as i64),
+            Value::BigInt(b) => Self::Integer(*b),
+            Value::BigIntUnsigned(b) => match i64::try_from(*b) {
+                Ok(b) => Self::Integer(b),
+                // SQLite has no unsigned 64-bit integers, so store those that don't fit as text
+                Err(_) => Self::String(b.to_string()),
+            },
+            Value::Int(i) => Self::Integer(*i as i64),
+            Value::IntUnsigned(i) => Self::Integer(*i as i64),
+            Value::SmallInt(s) => Self::Integer(*s as i64),
+            Value::SmallIntUnsigned(s) => Self::Integer(*s as i64),
+            Value::TinyInt(t) => Self::Integer(*t as i64),
+            Value::TinyIntUnsigned(t) => Self::Integer(*t as i64),
+            Value::Float(f) => Self::Float(*f as f64),
+            Value::Double(d) => Self::Float(*d),
+            Value::Decimal(d) => Self::String(d.to_string()),
+
+            Value::String(s) => Self::String(s.clone()),
+
+            Value::CurrentTimestamp => Self::String(Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+            Value::Date(d) => Self::String(d.format(DATE_FORMAT).to_string()),
+            Value::Time(t) => Self::String(t.format(TIME_FORMAT).to_string()),
+            Value::DateTime(dt) => Self::String(dt.format(DATETIME_FORMAT).to_string()),
+            Value::DateTimeTz(dt) => Self::String(dt.format(DATETIME_TZ_FORMAT).to_string()),
+
+            Value::Blob(b) => Self::Binary(b.clone()),
+            Value::Clob(c) => Self::String(c.clone()),
+            Value::Json(j) => Self::String(j.to_string()),
+            Value::Uuid(u) => Self::String(u.hyphenated().to_string()),
+            Value::Null => Self::Null,
+        }
+    }
+}
+
+
+/// Closes a raw connection opened for a backup when dropped.
+struct RawConnection(*mut ffi::sqlite3);
+impl Drop for RawConnection {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: The connection was opened by `sqlite3_open_v2()` (or is NULL, which is a no-op) and is not used after this.
+        unsafe { ffi::sqlite3_close(self.0) };
+    }
+}
+
+/// Removes the file of a temporary database when dropped.
+struct TempFile(PathBuf);
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // Also remove the journals SQLite may have left
+        for suffix in ["", "-journal", "-wal", "-shm"] {
+            let mut path: PathBuf = self.0.clone();
+            path.as_mut_os_string().push(suffix);
+            if let Err(err) = fs::remove_file(&path) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove temporary database file '{}': {err}", path.display());
+                }
+            }
+        }
+    }
+}
+
+/// The state shared by all clones of a [`Database`].
+///
+/// Note that the fields are dropped in order, so the connections are closed before any temporary file is removed.
+struct Inner {
+    /// The connection through which all writes (and transactions) go.
+    writer:  Mutex<Connection>,
+    /// Idle read-only connections, used for reads when the database is in WAL-mode.
+    readers: Mutex<Vec<Connection>>,
+    /// The path and options to open new readers with, or [`None`] if reads go through the writer.
+    source:  Option<(PathBuf, ConnectionOptions)>,
+    /// How statements that fail with transient errors are retried.
+    retry:   RetryPolicy,
+    /// The file of a temporary database, which is removed after the connections are closed.
+    _temp:   Option<TempFile>,
+}
+
+/// A connection to read from, as given by [`Database::reader()`].
+enum ReadConn<'d> {
+    /// Reads go through the writer.
+    Writer(MutexGuard<'d, Connection>),
+    /// A read-only connection, which is returned to the pool when dropped.
+    Pooled { conn: Option<Connection>, inner: &'d Inner },
+}
+impl<'d> Deref for ReadConn<'d> {
+    type Target = Connection;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Writer(conn) => conn,
+            Self::Pooled { conn, .. } => conn.as_ref().expect("reader is only taken when dropped"),
+        }
+    }
+}
+impl<'d> Drop for ReadConn<'d> {
+    fn drop(&mut self) {
+        if let Self::Pooled { conn, inner } = self {
+            let max_readers: usize = inner.source.as_ref().map(|(_, options)| options.max_readers).unwrap_or(0);
+            let mut readers: MutexGuard<Vec<Connection>> = inner.readers.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(conn) = conn.take() {
+                if readers.len() < max_readers {
+                    readers.push(conn);
+                }
+            }
+        }
+    }
+}
+
+/// Reads the last error of a raw connection.
+///
+/// # Arguments
+/// - `conn`: The raw connection to read the error of. May be NULL.
+/// - `code`: The error code returned by the failed call.
+///
+/// # Returns
+/// A new [`sqlite::Error`] with the code and SQLite's message for it.
+fn raw_error(conn: *mut ffi::sqlite3, code: c_int) -> sqlite::Error {
+    // SAFETY: `sqlite3_errmsg()` accepts any connection (including NULL) and returns a NUL-terminated string that lives until the next
+    // call on it, which we copy immediately.
+    let message: String = unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(conn)) }.to_string_lossy().into_owned();
+    sqlite::Error { code: Some(code as isize), message: Some(message) }
+}
+
+
+/// Executes the given SQL [`Statement`] on the given connection.
+///
+/// Note that the query is serialized as-is. To use a prepared statement, see [`execute_prepared_on()`].
+///
+/// Any results of the query are discarded. See [`query_raw_on()`] to send a query and return the rows.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to execute the statement on.
+/// - `stmt`: The [`Statement`] to execute.
+///
+/// # Returns
+/// An [`ExecuteResult`] with the number of rows affected by the statement and, for inserts, the `ROWID` of the last inserted row.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `stmt` for some reason.
+fn execute_on(conn: &Connection, stmt: &Statement) -> Result<ExecuteResult, Error> {
+    if stmt.is_noop() {
+        return Ok(ExecuteResult::default());
+    }
+
+    // Serialize directly and send
+    let query: String = serialize_sql(stmt, Dialect::Sqlite).to_string();
+    if let Err(err) = conn.execute(&query) {
+        return Err(Error::ExecuteFailed { query, err });
+    }
+
+    // Collect what happened
+    execute_result_on(conn, stmt)
+}
+
+/// Executes the given SQL [`Statement`] on the given connection as a prepared statement.
+///
+/// Any values in the statement are bound as parameters instead of serialized as literals.
+///
+/// Any results of the query are discarded. See [`query_raw_on()`] to send a query and return the rows.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to execute the statement on.
+/// - `stmt`: The [`Statement`] to execute.
+///
+/// # Returns
+/// An [`ExecuteResult`] with the number of rows affected by the statement and, for inserts, the `ROWID` of the last inserted row.
+///
+/// # Errors
+/// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+fn execute_prepared_on(conn: &Connection, stmt: &Statement) -> Result<ExecuteResult, Error> {
+    if stmt.is_noop() {
+        return Ok(ExecuteResult::default());
+    }
+
+    // Serialize with placeholders and prepare
+    let (query, params): (String, Vec<Value>) = serialize_sql_params(stmt, Dialect::Sqlite);
+    let mut prep: sqlite::Statement = match conn.prepare(&query) {
+        Ok(prep) => prep,
+        Err(err) => return Err(Error::ExecuteFailed { query, err }),
+    };
+
+    // Bind the parameters (which are 1-indexed)
+    for (i, value) in params.iter().enumerate() {
+        if let Err(err) = prep.bind((i + 1, &sqlite::Value::from(value))) {
+            return Err(Error::ExecuteFailed { query, err });
+        }
+    }
+
+    // Run it to completion
+    loop {
+        match prep.next() {
+            Ok(State::Row) => continue,
+            Ok(State::Done) => break,
+            Err(err) => return Err(Error::ExecuteFailed { query, err }),
+        }
+    }
+
+    // Collect what happened
+    execute_result_on(conn, stmt)
+}
+
+/// Executes a raw SQL query on the given connection, discarding any results.
+///
+/// This is an escape hatch for statements that cannot be expressed as a [`Statement`]. The query is sent as-is, so never build it
+/// from untrusted input.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to execute the query on.
+/// - `query`: The SQL query to execute. It may contain multiple statements.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `query` for some reason.
+fn execute_raw_on(conn: &Connection, query: &str) -> Result<(), Error> {
+    match conn.execute(query) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(Error::ExecuteFailed { query: query.into(), err }),
+    }
+}
+
+/// Executes the given SQL [`Statement`] on the given connection and calls the given closure for every resulting row as it is read.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to execute the statement on.
+/// - `stmt`: The [`Statement`] to execute.
+/// - `func`: The closure to call for every [`Row`]. If it errors, no more rows are read.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `stmt` or read its results, or if `func` errors.
+#[inline]
+fn for_each_row_on<E>(conn: &Connection, stmt: &Statement, func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+where
+    E: From<Error>,
+{
+    if stmt.is_noop() {
+        return Ok(());
+    }
+    for_each_row_raw_on(conn, serialize_sql(stmt, Dialect::Sqlite).to_string(), &[], func)
+}
+
+/// Returns the names of all tables in the database on the given connection, in alphabetical order.
+///
+/// SQLite's internal tables (e.g., `sqlite_sequence`) are omitted.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to query.
+///
+/// # Errors
+/// This function errors if we failed to query the database for them.
+fn tables_on(conn: &Connection) -> Result<Vec<Ident>, Error> {
+    let rows: Vec<Row> =
+        query_raw_on(conn, "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY name;", &[])?;
+    Ok(rows.iter().filter_map(|row| row_str(row, 0)).map(Ident::from).collect())
+}
+
+/// Reads the definition of a table back from the database on the given connection.
+///
+/// Note that SQLite only remembers the type names of columns, and that this crate creates most non-numeric columns as `TEXT`
+/// (see [`Type`](crate::sql::Type)). Column types that are not understood are read as `TEXT`.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to query.
+/// - `table`: The name of the table to describe.
+///
+/// # Returns
+/// A [`StatementCreateTable`] that creates the table (without its indices; see [`indexes_on()`]).
+///
+/// # Errors
+/// This function errors if we failed to query the database, if the table doesn't exist, if it has a composite primary key or if
+/// any of its foreign keys implicitly refer to a primary key.
+fn describe_table_on(conn: &Connection, table: &Ident) -> Result<StatementCreateTable, Error> {
+    let params: [Value; 1] = [Value::String(table.as_str().into())];
+
+    // The table's original SQL tells us whether its key auto-increments
+    let sql: String = match query_raw_on(conn, "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?;", &params)?.first() {
+        Some(row) => row_str(row, 0).unwrap_or_default().to_uppercase(),
+        None => return Err(Error::IntrospectFailed { table: table.clone(), reason: "no such table" }),
+    };
+
+    // Read the columns
+    let mut def: StatementCreateTable = StatementCreateTable::new(table);
+    let mut primary_key: Vec<Ident> = vec![];
+    for row in query_raw_on(conn, "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?) ORDER BY cid;", &params)? {
+        let name: Ident = row_str(&row, 0).unwrap_or_default().into();
+        if row_int(&row, 4).unwrap_or(0) > 0 {
+            primary_key.push(name.clone());
+        }
+        def.cols.push(introspect_column(table, name, row_str(&row, 1).unwrap_or_default(), row_int(&row, 2) == Some(1), row_str(&row, 3)));
+    }
+    match primary_key.len() {
+        0 => {},
+        1 => {
+            // Only an `INTEGER PRIMARY KEY` can be `AUTOINCREMENT`
+            let key: Ident = primary_key.remove(0);
+            if sql.contains("AUTOINCREMENT") {
+                if let Some(col) = def.cols.iter_mut().find(|c| c.name == key) {
+                    col.auto_increment = true;
+                }
+            }
+            def.primary_key = Some(key);
+        },
+        _ => return Err(Error::IntrospectFailed { table: table.clone(), reason: "composite primary keys are not supported" }),
+    }
+
+    // Read the foreign keys, which span multiple rows if they have multiple columns
+    let mut fks: Vec<(i64, ForeignKey)> = vec![];
+    for row in query_raw_on(conn, "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete FROM pragma_foreign_key_list(?) ORDER BY id, seq;", &params)? {
+        let (col, ref_col): (&str, &str) = match (row_str(&row, 2), row_str(&row, 3)) {
+            (Some(col), Some(ref_col)) => (col, ref_col),
+            _ => return Err(Error::IntrospectFailed { table: table.clone(), reason: "foreign keys must name the columns they refer to" }),
+        };
+        let id: i64 = row_int(&row, 0).unwrap_or(0);
+        match fks.last_mut() {
+            Some((prev, fk)) if *prev == id => {
+                fk.cols.push(col.into());
+                fk.ref_cols.push(ref_col.into());
+            },
+            _ => {
+                // The database's default is the same as not specifying it
+                let action = |i: usize| row_str(&row, i).and_then(ReferentialAction::from_sql).filter(|a| *a != ReferentialAction::NoAction);
+                let mut fk: ForeignKey = ForeignKey::new([col], row_str(&row, 1).unwrap_or_default(), [ref_col]);
+                fk.on_update = action(4);
+                fk.on_delete = action(5);
+                fks.push((id, fk));
+            },
+        }
+    }
+    def.foreign_keys = fks.into_iter().map(|(_, fk)| fk).collect();
+
+    debug!("Described table '{table}' with {} columns", def.cols.len());
+    Ok(def)
+}
+
+/// Reads the indices on a table back from the database on the given connection.
+///
+/// This includes those SQLite creates for `UNIQUE` constraints, which are renamed to `<table>_<columns>_key` as names starting
+/// with `sqlite_` are reserved. The index backing the primary key is omitted.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to query.
+/// - `table`: The name of the table to read the indices of.
+///
+/// # Returns
+/// A list of [`StatementCreateIndex`]es that create the indices. Indices on expressions are skipped.
+///
+/// # Errors
+/// This function errors if we failed to query the database for them.
+fn indexes_on(conn: &Connection, table: &Ident) -> Result<Vec<StatementCreateIndex>, Error> {
+    let mut indexes: Vec<StatementCreateIndex> = vec![];
+    for row in query_raw_on(conn, "SELECT name, \"unique\", origin FROM pragma_index_list(?) ORDER BY seq;", &[Value::String(table.as_str().into())])? {
+        let (name, origin): (&str, &str) = (row_str(&row, 0).unwrap_or_default(), row_str(&row, 2).unwrap_or_default());
+        if origin == "pk" {
+            continue;
+        }
+
+        // Read the indexed columns, which have no name if they are expressions
+        let cols: Vec<Row> = query_raw_on(conn, "SELECT name FROM pragma_index_info(?) ORDER BY seqno;", &[Value::String(name.into())])?;
+        let cols: Vec<Ident> = match cols.iter().map(|col| row_str(col, 0).map(Ident::from)).collect() {
+            Some(cols) => cols,
+            None => {
+                warn!("Skipping index '{name}' on table '{table}' as it indexes expressions");
+                continue;
+            },
+        };
+
+        let name: Ident = if origin == "u" {
+            format!("{table}_{}_key", cols.iter().map(Ident::as_str).collect::<Vec<&str>>().join("_")).into()
+        } else {
+            name.into()
+        };
+        indexes.push(StatementCreateIndex::new(name, table, cols).unique(row_int(&row, 1) == Some(1)));
+    }
+    Ok(indexes)
+}
+
+/// Executes a raw SQL query with the given parameters and returns the resulting rows.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to execute the query on.
+/// - `query`: The SQL query to execute, with `?` as placeholders for the `params`.
+/// - `params`: The values to bind to the placeholders.
+///
+/// # Returns
+/// A list of [`Row`]s produced by the query.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `query` or read its results.
+fn query_raw_on(conn: &Connection, query: &str, params: &[Value]) -> Result<Vec<Row>, Error> {
+    let mut rows: Vec<Row> = vec![];
+    for_each_row_raw_on(conn, query.into(), params, |row| -> Result<(), Error> {
+        rows.push(row);
+        Ok(())
+    })?;
+    Ok(rows)
+}
+
+/// Executes a raw SQL query with the given parameters and calls the given closure for every resulting row as it is read.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to execute the query on.
+/// - `query`: The SQL query to execute, with `?` as placeholders for the `params`.
+/// - `params`: The values to bind to the placeholders.
+/// - `func`: The closure to call for every [`Row`]. If it errors, no more rows are read.
+///
+/// # Errors
+/// This function errors if we failed to execute the given `query` or read its results, or if `func` errors.
+fn for_each_row_raw_on<E>(conn: &Connection, query: String, params: &[Value], mut func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+where
+    E: From<Error>,
+{
+    let mut prep: sqlite::Statement = match conn.prepare(&query) {
+        Ok(prep) => prep,
+        Err(err) => return Err(Error::QueryFailed { query, err }.into()),
+    };
+    for (i, value) in params.iter().enumerate() {
+        if let Err(err) = prep.bind((i + 1, &sqlite::Value::from(value))) {
+            return Err(Error::QueryFailed { query, err }.into());
+        }
+    }
+
+    // Step through the rows
+    let cols: Arc<[String]> = prep.column_names().into();
+    loop {
+        match prep.next() {
+            Ok(State::Row) => {
+                let mut values: Vec<Value> = Vec::with_capacity(cols.len());
+                for i in 0..cols.len() {
+                    match prep.read::<sqlite::Value, _>(i) {
+                        Ok(value) => values.push(value.into()),
+                        Err(err) => return Err(Error::QueryFailed { query, err }.into()),
+                    }
+                }
+                func(Row { cols: cols.clone(), values })?;
+            },
+            Ok(State::Done) => return Ok(()),
+            Err(err) => return Err(Error::QueryFailed { query, err }.into()),
+        }
+    }
+}
+
+/// Collects the [`ExecuteResult`] of the statement that was just executed.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] the statement was executed on.
+/// - `stmt`: The [`Statement`] that was executed.
+///
+/// # Returns
+/// An [`ExecuteResult`] describing what the statement did.
+///
+/// # Errors
+/// This function errors if we failed to query the backend for the last inserted row.
+fn execute_result_on(conn: &Connection, stmt: &Statement) -> Result<ExecuteResult, Error> {
+    match stmt {
+        Statement::Insert(_) => {
+            // The last inserted ROWID is that of an earlier statement if this one inserted nothing (e.g., `INSERT OR IGNORE`)
+            let rows_affected: u64 = conn.change_count() as u64;
+            let last_insert_id: Option<u64> = if rows_affected > 0 { last_insert_rowid_on(conn)? } else { None };
+            Ok(ExecuteResult { rows_affected, last_insert_id })
+        },
+        Statement::Update(_) | Statement::Delete(_) => Ok(ExecuteResult { rows_affected: conn.change_count() as u64, last_insert_id: None }),
+        Statement::CreateTable(_) | Statement::CreateIndex(_) | Statement::Select(_) | Statement::UseDatabase(_) => Ok(ExecuteResult::default()),
+    }
+}
+
+/// Retrieves the `ROWID` of the row last inserted on this connection.
+///
+/// # Arguments
+/// - `conn`: The [`Connection`] to query.
+///
+/// # Returns
+/// The `ROWID`, or [`None`] if no row was inserted yet (or it is negative).
+///
+/// # Errors
+/// This function errors if we failed to query the backend for it.
+fn last_insert_rowid_on(conn: &Connection) -> Result<Option<u64>, Error> {
+    let mut prep: sqlite::Statement = match conn.prepare("SELECT last_insert_rowid();") {
+        Ok(prep) => prep,
+        Err(err) => return Err(Error::LastInsertId { err }),
+    };
+    match prep.next() {
+        Ok(State::Row) => match prep.read::<i64, _>(0) {
+            Ok(id) => Ok(if id > 0 { Some(id as u64) } else { None }),
+            Err(err) => Err(Error::LastInsertId { err }),
+        },
+        Ok(State::Done) => Ok(None),
+        Err(err) => Err(Error::LastInsertId { err }),
+    }
+}
+
+
 /// Defines a file with the SQLite config such that we know how to connect to the database.
+///
+/// Besides the path, this may contain any of the [`ConnectionOptions`], e.g.:
+/// ```yaml
+/// path: ./data.db
+/// journal_mode: wal
+/// synchronous: normal
+/// busy_timeout: 5000
+/// foreign_keys: true
+/// pragmas:
+///   temp_store: memory
+/// ```
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConfigFile {
     /// The path to the database file.
-    path: PathBuf,
+    pub(crate) path:    PathBuf,
+    /// How to open and tune the connection.
+    #[serde(flatten)]
+    pub(crate) options: ConnectionOptions,
+}
+impl ConfigFile {
+    /// Parses a ConfigFile from the given connection URL.
+    ///
+    /// The URL is either `sqlite:///absolute/path.db` or `sqlite:relative/path.db`. Any of the [`ConnectionOptions`] can be given as
+    /// query parameters, e.g., `sqlite:///var/lib/app.db?journal_mode=wal&busy_timeout=5000`. In addition:
+    /// - `mode` may be `ro` (read-only), `rw` (don't create the database) or `rwc` (create the database); and
+    /// - `pragma.<name>` sets one of the `pragmas` in the [`ConnectionOptions`], e.g., `pragma.temp_store=memory`.
+    ///
+    /// # Arguments
+    /// - `url`: The URL to parse.
+    ///
+    /// # Returns
+    /// A new ConfigFile with the path and options in the URL.
+    ///
+    /// # Errors
+    /// This function errors if `url` is not a valid SQLite URL, e.g., because it has an unknown parameter.
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let redacted: String = redact_url(url);
+        let parsed: Url = match Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(err) => return Err(Error::UrlParse { url: redacted, err }),
+        };
+        if parsed.scheme() != "sqlite" {
+            return Err(Error::UrlInvalid { url: redacted, reason: format!("expected scheme 'sqlite', got '{}'", parsed.scheme()) });
+        }
+        if parsed.host_str().map(|host| !host.is_empty()).unwrap_or(false) {
+            return Err(Error::UrlInvalid { url: redacted, reason: "SQLite URLs cannot have a host (use 'sqlite:///' for absolute paths)".into() });
+        }
+
+        // Get the path
+        let path: PathBuf = PathBuf::from(percent_decode_str(parsed.path()).decode_utf8_lossy().into_owned());
+        if path.as_os_str().is_empty() {
+            return Err(Error::UrlInvalid { url: redacted, reason: "missing database path".into() });
+        }
+
+        // Get the options
+        let mut options: ConnectionOptions = ConnectionOptions::default();
+        for (key, value) in parsed.query_pairs() {
+            match key.as_ref() {
+                "mode" => match value.as_ref() {
+                    "ro" => options.read_only = true,
+                    "rw" => options.create = false,
+                    "rwc" => options.create = true,
+                    _ => {
+                        return Err(Error::UrlInvalid { url: redacted, reason: format!("invalid value '{value}' for parameter 'mode'") });
+                    },
+                },
+                "journal_mode" => options.journal_mode = Some(url_param_enum(&redacted, &key, &value)?),
+                "synchronous" => options.synchronous = Some(url_param_enum(&redacted, &key, &value)?),
+                "busy_timeout" => options.busy_timeout = Some(url_param(&redacted, &key, &value)?),
+                "foreign_keys" => match parse_url_bool(&value) {
+                    Some(value) => options.foreign_keys = Some(value),
+                    None => {
+                        return Err(Error::UrlInvalid { url: redacted, reason: format!("invalid value '{value}' for parameter 'foreign_keys'") });
+                    },
+                },
+                "cache_size" => options.cache_size = Some(url_param(&redacted, &key, &value)?),
+                "mmap_size" => options.mmap_size = Some(url_param(&redacted, &key, &value)?),
+                "max_readers" => options.max_readers = url_param(&redacted, &key, &value)?,
+                _ => match key.strip_prefix("pragma.") {
+                    Some(pragma) if !pragma.is_empty() => {
+                        let value: Value = match value.parse() {
+                            Ok(value) => Value::BigInt(value),
+                            Err(_) => Value::String(value.into_owned()),
+                        };
+                        options.pragmas.insert(Ident::new(pragma), value);
+                    },
+                    _ => return Err(Error::UrlInvalid { url: redacted, reason: format!("unknown parameter '{key}'") }),
+                },
+            }
+        }
+
+        // Done
+        Ok(Self { path, options })
+    }
+}
+impl FromStr for ConfigFile {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::from_url(s) }
+}
+
+/// Defines how a connection to an SQLite database is opened and tuned.
+///
+/// Any option that is not given keeps SQLite's default.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConnectionOptions {
+    /// The journal mode (`PRAGMA journal_mode`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal_mode: Option<JournalMode>,
+    /// How often SQLite syncs to disk (`PRAGMA synchronous`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synchronous:  Option<Synchronous>,
+    /// How long to wait for locks held by other connections, in milliseconds (`PRAGMA busy_timeout`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy_timeout: Option<u64>,
+    /// Whether to enforce foreign keys (`PRAGMA foreign_keys`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign_keys: Option<bool>,
+    /// The size of the page cache; in pages if positive, or in KiB if negative (`PRAGMA cache_size`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_size:   Option<i64>,
+    /// The maximum number of bytes of the database to memory-map (`PRAGMA mmap_size`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mmap_size:    Option<u64>,
+    /// Whether to open the database read-only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only:    bool,
+    /// Whether to create the database if it doesn't exist. Ignored if `read_only` is set.
+    #[serde(default = "default_create")]
+    pub create:       bool,
+    /// Any other pragmas to set, which are applied after the ones above. These are set on every connection, including readers.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pragmas:      BTreeMap<Ident, Value>,
+    /// The maximum number of idle read-only connections to keep. These are only used if `journal_mode` is [`JournalMode::Wal`], such
+    /// that reads can run concurrently with the writer. Set to 0 to send all reads through the writer.
+    #[serde(default = "default_max_readers")]
+    pub max_readers:  usize,
+    /// How statements that fail with transient errors (e.g., a busy database) are retried. Note that these retries come on top of
+    /// waiting for the `busy_timeout`.
+    #[serde(default)]
+    pub retry:        RetryPolicy,
+}
+impl ConnectionOptions {
+    /// Returns the pragmas to execute for these options.
+    ///
+    /// # Arguments
+    /// - `reader`: Whether the pragmas are for a read-only connection, which leaves out those that change the database file.
+    ///
+    /// # Returns
+    /// A list of `PRAGMA`-statements, in the order they should be executed.
+    fn pragmas(&self, reader: bool) -> Vec<String> {
+        let mut pragmas: Vec<String> = vec![];
+        if let (Some(mode), false) = (self.journal_mode, reader) {
+            pragmas.push(format!("PRAGMA journal_mode = {};", mode.as_sql()));
+        }
+        if let (Some(sync), false) = (self.synchronous, reader) {
+            pragmas.push(format!("PRAGMA synchronous = {};", sync.as_sql()));
+        }
+        if let Some(timeout) = self.busy_timeout {
+            pragmas.push(format!("PRAGMA busy_timeout = {timeout};"));
+        }
+        if let Some(foreign_keys) = self.foreign_keys {
+            pragmas.push(format!("PRAGMA foreign_keys = {};", if foreign_keys { "ON" } else { "OFF" }));
+        }
+        if let Some(size) = self.cache_size {
+            pragmas.push(format!("PRAGMA cache_size = {size};"));
+        }
+        if let Some(size) = self.mmap_size {
+            pragmas.push(format!("PRAGMA mmap_size = {size};"));
+        }
+        for (name, value) in &self.pragmas {
+            pragmas.push(format!("PRAGMA {} = {};", serialize_sql(name, Dialect::Sqlite), serialize_sql(value, Dialect::Sqlite)));
+        }
+        pragmas
+    }
+}
+impl Default for ConnectionOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            journal_mode: None,
+            synchronous:  None,
+            busy_timeout: None,
+            foreign_keys: None,
+            cache_size:   None,
+            mmap_size:    None,
+            read_only:    false,
+            create:       default_create(),
+            pragmas:      BTreeMap::new(),
+            max_readers:  default_max_readers(),
+            retry:        RetryPolicy::default(),
+        }
+    }
+}
+
+/// Defines the journal modes of SQLite (see `PRAGMA journal_mode`).
+#[derive(Clone, Copy, Debug, Deserialize, EnumDebug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalMode {
+    /// The journal is deleted after every transaction (the default).
+    Delete,
+    /// The journal is truncated after every transaction.
+    Truncate,
+    /// The journal's header is zeroed after every transaction.
+    Persist,
+    /// The journal is kept in memory.
+    Memory,
+    /// A write-ahead log is used, which allows readers to run concurrently with a writer.
+    Wal,
+    /// No journal is kept, disabling rollbacks.
+    Off,
+}
+impl JournalMode {
+    /// Returns the name of this mode in SQL.
+    ///
+    /// # Returns
+    /// The value to assign to `PRAGMA journal_mode`.
+    #[inline]
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Delete => "DELETE",
+            Self::Truncate => "TRUNCATE",
+            Self::Persist => "PERSIST",
+            Self::Memory => "MEMORY",
+            Self::Wal => "WAL",
+            Self::Off => "OFF",
+        }
+    }
+}
+
+/// Defines how often SQLite syncs to disk (see `PRAGMA synchronous`).
+#[derive(Clone, Copy, Debug, Deserialize, EnumDebug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Synchronous {
+    /// Never sync, leaving it to the OS.
+    Off,
+    /// Sync at critical moments, which is safe in WAL mode.
+    Normal,
+    /// Sync before every commit (the default).
+    Full,
+    /// Like [`Synchronous::Full`], but also syncs the directory of the journal.
+    Extra,
+}
+impl Synchronous {
+    /// Returns the name of this level in SQL.
+    ///
+    /// # Returns
+    /// The value to assign to `PRAGMA synchronous`.
+    #[inline]
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Off => "OFF",
+            Self::Normal => "NORMAL",
+            Self::Full => "FULL",
+            Self::Extra => "EXTRA",
+        }
+    }
 }
 
 
@@ -82,10 +1042,27 @@ pub struct ConfigFile {
 
 
 /***** LIBRARY *****/
+/// Describes how far a backup by [`Database::backup_to()`] has progressed.
+#[derive(Clone, Copy, Debug)]
+pub struct BackupProgress {
+    /// The number of pages still to copy.
+    pub remaining: u64,
+    /// The total number of pages in the database.
+    pub total:     u64,
+}
+
+
+
 /// Implementation of a [`spec::Database`] for an SQLite backend.
+///
+/// The Database is `Send + Sync`, and cloning it is cheap: clones share the same connections, so it can be handed to multiple
+/// threads. All writes go through a single connection, one at a time. If the database is opened in WAL-mode (see
+/// [`ConnectionOptions::journal_mode`]), reads (i.e., `SELECT`s and introspection) are served by a pool of read-only connections and
+/// run concurrently with the writer and each other; otherwise, they go through the writer as well.
+#[derive(Clone)]
 pub struct Database {
-    /// The connection to the database.
-    conn: Connection,
+    /// The connections shared by all clones.
+    inner: Arc<Inner>,
 }
 impl Database {
     /// Constructor for the Database that initializes it pointing to a particular database.
@@ -101,6 +1078,25 @@ impl Database {
     /// This function may error if we failed to connect to the given endpoint.
     #[inline]
     pub fn new<F>(path: impl AsRef<Path>, init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        Self::with_options(path, &ConnectionOptions::default(), init)
+    }
+
+    /// Constructor for the Database that initializes it pointing to a particular database, opened and tuned with the given options.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the database file.
+    /// - `options`: The [`ConnectionOptions`] that determine how the database is opened and which pragmas are set on it.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it. It runs after the pragmas are set.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to connect to the given endpoint or to set any of the pragmas.
+    pub fn with_options<F>(path: impl AsRef<Path>, options: &ConnectionOptions, init: F) -> Result<Self, Error>
     where
         F: FnOnce(&Self) -> Result<(), Error>,
     {
@@ -112,71 +1108,1071 @@ impl Database {
 
         // Attempt to open the connection
         debug!("Opening connection to '{}'...", path.display());
-        let conn: Connection = match sqlite::open(path) {
+        let flags: OpenFlags = if options.read_only {
+            OpenFlags::new().with_read_only()
+        } else if options.create {
+            OpenFlags::new().with_read_write().with_create()
+        } else {
+            OpenFlags::new().with_read_write()
+        };
+        let conn: Connection = match Connection::open_with_flags(path, flags) {
             Ok(conn) => conn,
             Err(err) => return Err(Error::DatabaseOpen { path: path.into(), err }),
         };
 
-        // Run the init, if necessary
-        let this: Self = Self { conn };
-        if run_init {
-            debug!("Initializing database at '{}' with {}", path.display(), type_name::<F>());
-            if let Err(err) = init(&this) {
-                return Err(Error::InitFailed { path: path.into(), err: Box::new(err) });
-            }
+        // Tune it
+        for pragma in options.pragmas(false) {
+            debug!("Setting '{pragma}' on '{}'", path.display());
+            execute_raw_on(&conn, &pragma)?;
         }
 
-        // OK, return ourselves
-        Ok(this)
+        // Only in WAL-mode can readers run alongside the writer
+        let source: Option<(PathBuf, ConnectionOptions)> =
+            if options.journal_mode == Some(JournalMode::Wal) && options.max_readers > 0 { Some((path.into(), options.clone())) } else { None };
+        let this: Self = Self::from_conn(conn, source, options.retry.clone(), None);
+
+        // Run the init, if necessary
+        this.init(path, run_init, init)
     }
 
-    /// Constructor for the Database that initializes it pointing to a particular database.
+    /// Constructor for a Database that lives in memory only.
+    ///
+    /// As the database is new, `init` is always executed.
     ///
     /// # Arguments
-    /// - `cfg_path`: The path to the [`ConfigFile`] that we'll be reading.
-    /// - `init`: If the database did not previously exist, this code may be executed to initialize it.
+    /// - `init`: Code that is executed to initialize the database.
     ///
     /// # Returns
-    /// A new instance of Self that can be used to communicate to a backend database.
+    /// A new instance of Self that can be used to communicate to a database that is gone once it is dropped.
     ///
     /// # Errors
-    /// This function may error if we failed to read the given file or if we failed to connect to the given endpoint.
-    pub fn from_path<F>(cfg_path: impl AsRef<Path>, init: F) -> Result<Self, Error>
+    /// This function may error if we failed to create the database or if `init` fails.
+    pub fn in_memory<F>(init: F) -> Result<Self, Error>
     where
         F: FnOnce(&Self) -> Result<(), Error>,
     {
-        let cfg_path: &Path = cfg_path.as_ref();
-        info!("Initializing SQLite database by reading the options from '{}'", cfg_path.display());
-
-        // Defer to the common part
-        match load_config_file::<ConfigFile>(cfg_path) {
-            Ok(config) => {
-                // Now call the normal initializer with these options
-                Self::new(config.path, init)
-            },
-            Err(err) => Err(Error::ConfigLoad { err }),
-        }
+        info!("Initializing in-memory SQLite database");
+        let conn: Connection = match sqlite::open(":memory:") {
+            Ok(conn) => conn,
+            Err(err) => return Err(Error::DatabaseOpen { path: ":memory:".into(), err }),
+        };
+        Self::from_conn(conn, None, RetryPolicy::default(), None).init(Path::new(":memory:"), true, init)
     }
 
-    /// Executes the given SQL [`Statement`] on the backend.
+    /// Constructor for a Database that lives in memory only, but that can be opened multiple times in the same process by name.
     ///
-    /// Note that the query is serialized as-is. To use a prepared statement, see `Self::execute_prepared()`.
+    /// The database lives as long as any of the Databases opened with the same `name`. It is initialized with `init` if it has no tables
+    /// yet, i.e., when it is first opened (unless `init` creates no tables).
     ///
-    /// Any results of the query are discarded. See `Self::query()` to send a statement and return the rows.
+    /// # Arguments
+    /// - `name`: The name of the database, which identifies it within this process.
+    /// - `init`: Code that is executed to initialize the database if it is new.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a shared in-memory database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to open the database or if `init` fails.
+    pub fn in_memory_shared<F>(name: impl AsRef<str>, init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        let name: &str = name.as_ref();
+        info!("Initializing shared in-memory SQLite database '{name}'");
+
+        // Open it by URI, such that we can ask for a named, shared database
+        let uri: String = format!("file:{name}?mode=memory&cache=shared");
+        let conn: Connection = match Connection::open_with_flags(&uri, OpenFlags::new().with_create().with_read_write().with_uri()) {
+            Ok(conn) => conn,
+            Err(err) => return Err(Error::DatabaseOpen { path: uri.into(), err }),
+        };
+        let this: Self = Self::from_conn(conn, None, RetryPolicy::default(), None);
+        let run_init: bool = this.tables()?.is_empty();
+        this.init(Path::new(&uri), run_init, init)
+    }
+
+    /// Constructor for a Database that is backed by a temporary file, which is removed once the Database is dropped.
+    ///
+    /// As the database is new, `init` is always executed.
+    ///
+    /// # Arguments
+    /// - `init`: Code that is executed to initialize the database.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a temporary database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to create the database file or if `init` fails.
+    pub fn temporary<F>(init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        // Find a name unique to this process and time
+        let nanos: u128 = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let name: String = format!("database-{}-{}-{nanos}.sqlite", process::id(), TEMP_COUNTER.fetch_add(1, Ordering::Relaxed));
+        let path: PathBuf = std::env::temp_dir().join(name);
+        info!("Initializing temporary SQLite database at '{}'", path.display());
+
+        // Create the file, which the guard removes once the connection is closed
+        let conn: Connection = match sqlite::open(&path) {
+            Ok(conn) => conn,
+            Err(err) => return Err(Error::DatabaseOpen { path, err }),
+        };
+        Self::from_conn(conn, None, RetryPolicy::default(), Some(TempFile(path.clone()))).init(&path, true, init)
+    }
+
+    /// Constructor for the Database that initializes it pointing to a particular database.
+    ///
+    /// The [`ConnectionOptions`] in the file are applied as by [`Database::with_options()`].
+    ///
+    /// # Arguments
+    /// - `cfg_path`: The path to the [`ConfigFile`] that we'll be reading.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to read the given file or if we failed to connect to the given endpoint.
+    pub fn from_path<F>(cfg_path: impl AsRef<Path>, init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        let cfg_path: &Path = cfg_path.as_ref();
+        info!("Initializing SQLite database by reading the options from '{}'", cfg_path.display());
+
+        // Defer to the common part
+        match load_config_file::<ConfigFile>(cfg_path) {
+            Ok(config) => {
+                // Now call the normal initializer with these options
+                Self::from_config(config, init)
+            },
+            Err(err) => Err(Error::ConfigLoad { err }),
+        }
+    }
+
+    /// Constructor for the Database that initializes it pointing to the database in the given [`ConfigFile`].
+    ///
+    /// This is useful for configs that are not read from a single file, e.g., those loaded with a
+    /// [`ConfigLoader`](crate::common::ConfigLoader).
+    ///
+    /// # Arguments
+    /// - `config`: The [`ConfigFile`] with the path and options of the database.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if we failed to connect to the given endpoint.
+    #[inline]
+    pub fn from_config<F>(config: ConfigFile, init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        Self::with_options(config.path, &config.options, init)
+    }
+
+    /// Constructor for the Database that initializes it pointing to the database in the given connection URL.
+    ///
+    /// See [`ConfigFile::from_url()`] for the format of the URL.
+    ///
+    /// # Arguments
+    /// - `url`: The connection URL to parse.
+    /// - `init`: If the database did not previously exist, this code may be executed to initialize it.
+    ///
+    /// # Returns
+    /// A new instance of Self that can be used to communicate to a backend database.
+    ///
+    /// # Errors
+    /// This function may error if the URL is invalid or if we failed to connect to the given endpoint.
+    pub fn from_url<F>(url: impl AsRef<str>, init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        let config: ConfigFile = ConfigFile::from_url(url.as_ref())?;
+        info!("Initializing SQLite database from URL with path '{}'", config.path.display());
+        Self::from_config(config, init)
+    }
+
+    /// Wraps a newly opened connection in a Database.
+    ///
+    /// # Arguments
+    /// - `conn`: The [`Connection`] to use as writer.
+    /// - `source`: The path and options to open readers with, if reads should not go through the writer.
+    /// - `retry`: The [`RetryPolicy`] with which to retry statements.
+    /// - `temp`: The file of a temporary database, if any.
+    ///
+    /// # Returns
+    /// A new Database that has no readers yet.
+    #[inline]
+    fn from_conn(conn: Connection, source: Option<(PathBuf, ConnectionOptions)>, retry: RetryPolicy, temp: Option<TempFile>) -> Self {
+        Self { inner: Arc::new(Inner { writer: Mutex::new(conn), readers: Mutex::new(vec![]), source, retry, _temp: temp }) }
+    }
+
+    /// Runs the initializer of a newly opened Database, if necessary.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the database. Only used for logging and errors.
+    /// - `run_init`: Whether to run `init`.
+    /// - `init`: The code that is executed to initialize the database.
+    ///
+    /// # Returns
+    /// Self once initialized.
+    ///
+    /// # Errors
+    /// This function errors if `init` fails.
+    fn init<F>(self, path: &Path, run_init: bool, init: F) -> Result<Self, Error>
+    where
+        F: FnOnce(&Self) -> Result<(), Error>,
+    {
+        if run_init {
+            debug!("Initializing database at '{}' with {}", path.display(), type_name::<F>());
+            if let Err(err) = init(&self) {
+                return Err(Error::InitFailed { path: path.into(), err: Box::new(err) });
+            }
+        }
+
+        // OK, return ourselves
+        Ok(self)
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend.
+    ///
+    /// Note that the query is serialized as-is. To use a prepared statement, see `Self::execute_prepared()`.
+    ///
+    /// Any results of the query are discarded. See `Self::query()` to send a statement and return the rows.
+    ///
+    /// If the statement fails with a transient error, it is retried according to the [`RetryPolicy`] in the [`ConnectionOptions`].
     ///
     /// # Arguments
     /// - `stmt`: The [`Statement`] to execute.
     ///
+    /// # Returns
+    /// An [`ExecuteResult`] with the number of rows affected by the statement and, for inserts, the `ROWID` of the last inserted row.
+    ///
     /// # Errors
     /// This function errors if we failed to execute the given `stmt` for some reason.
-    pub fn execute(&self, stmt: impl AsRef<Statement>) -> Result<(), Error> {
+    #[inline]
+    pub fn execute(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        let stmt: &Statement = stmt.as_ref();
+        self.inner.retry.run(|| execute_on(&self.writer(), stmt))
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend as a prepared statement.
+    ///
+    /// Any values in the statement are bound as parameters instead of serialized as literals.
+    ///
+    /// Any results of the query are discarded. See `Self::query()` to send a statement and return the rows.
+    ///
+    /// If the statement fails with a transient error, it is retried according to the [`RetryPolicy`] in the [`ConnectionOptions`].
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// An [`ExecuteResult`] with the number of rows affected by the statement and, for inserts, the `ROWID` of the last inserted row.
+    ///
+    /// # Errors
+    /// This function errors if we failed to prepare or execute the given `stmt` for some reason.
+    #[inline]
+    pub fn execute_prepared(&self, stmt: impl AsRef<Statement>) -> Result<ExecuteResult, Error> {
+        let stmt: &Statement = stmt.as_ref();
+        self.inner.retry.run(|| execute_prepared_on(&self.writer(), stmt))
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend and returns the resulting rows.
+    ///
+    /// This can be used to retrieve the rows produced by a `RETURNING`-clause. If the statement fails with a transient error, it is
+    /// retried according to the [`RetryPolicy`] in the [`ConnectionOptions`].
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    ///
+    /// # Returns
+    /// A list of [`Row`]s produced by the statement.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `stmt` or read its results.
+    pub fn query(&self, stmt: impl AsRef<Statement>) -> Result<Vec<Row>, Error> {
+        let stmt: &Statement = stmt.as_ref();
+        self.inner.retry.run(|| {
+            let mut rows: Vec<Row> = vec![];
+            self.for_each_row(stmt, |row| -> Result<(), Error> {
+                rows.push(row);
+                Ok(())
+            })?;
+            Ok(rows)
+        })
+    }
+
+    /// Executes the given SQL [`Statement`] on the backend and calls the given closure for every resulting row as it is read.
+    ///
+    /// Unlike `Self::query()`, this doesn't keep all rows in memory. It is also never retried, as `func` may already have seen some rows
+    /// when the statement fails. Note that the connection is held while `func` runs, so it should not use this Database (or a clone of
+    /// it) to write.
+    ///
+    /// # Arguments
+    /// - `stmt`: The [`Statement`] to execute.
+    /// - `func`: The closure to call for every [`Row`]. If it errors, no more rows are read.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a reader, to execute the given `stmt` or read its results, or if `func` errors.
+    pub fn for_each_row<E>(&self, stmt: impl AsRef<Statement>, func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+    where
+        E: From<Error>,
+    {
         let stmt: &Statement = stmt.as_ref();
+        match stmt {
+            Statement::Select(_) => for_each_row_on(&*self.reader()?, stmt, func),
+            _ => for_each_row_on(&self.writer(), stmt, func),
+        }
+    }
+
+    /// Executes a raw SQL query on the backend, discarding any results.
+    ///
+    /// This is an escape hatch for statements that cannot be expressed as a [`Statement`]. The query is sent as-is, so never build it
+    /// from untrusted input.
+    ///
+    /// # Arguments
+    /// - `query`: The SQL query to execute. It may contain multiple statements.
+    ///
+    /// # Errors
+    /// This function errors if we failed to execute the given `query` for some reason.
+    #[inline]
+    pub fn execute_raw(&self, query: &str) -> Result<(), Error> { execute_raw_on(&self.writer(), query) }
+
+    /// Returns the names of all tables in the database, in alphabetical order.
+    ///
+    /// SQLite's internal tables (e.g., `sqlite_sequence`) are omitted.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a reader or to query the database for them.
+    #[inline]
+    pub fn tables(&self) -> Result<Vec<Ident>, Error> { tables_on(&*self.reader()?) }
+
+    /// Reads the definition of a table back from the database.
+    ///
+    /// Note that SQLite only remembers the type names of columns, and that this crate creates most non-numeric columns as `TEXT`
+    /// (see [`Type`](crate::sql::Type)). Column types that are not understood are read as `TEXT`.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to describe.
+    ///
+    /// # Returns
+    /// A [`StatementCreateTable`] that creates the table (without its indices; see `Self::indexes()`).
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a reader or to query the database, if the table doesn't exist, if it has a composite
+    /// primary key or if any of its foreign keys implicitly refer to a primary key.
+    #[inline]
+    pub fn describe_table(&self, table: &Ident) -> Result<StatementCreateTable, Error> { describe_table_on(&*self.reader()?, table) }
+
+    /// Reads the indices on a table back from the database.
+    ///
+    /// This includes those SQLite creates for `UNIQUE` constraints, which are renamed to `<table>_<columns>_key` as names starting
+    /// with `sqlite_` are reserved. The index backing the primary key is omitted.
+    ///
+    /// # Arguments
+    /// - `table`: The name of the table to read the indices of.
+    ///
+    /// # Returns
+    /// A list of [`StatementCreateIndex`]es that create the indices. Indices on expressions are skipped.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a reader or to query the database for them.
+    #[inline]
+    pub fn indexes(&self, table: &Ident) -> Result<Vec<StatementCreateIndex>, Error> { indexes_on(&*self.reader()?, table) }
+
+    /// Creates the given [`Schema`] in this database.
+    ///
+    /// This executes the statements returned by [`Schema::statements()`] in order, with seeded values bound as parameters.
+    ///
+    /// # Arguments
+    /// - `schema`: The [`Schema`] to apply.
+    ///
+    /// # Errors
+    /// This function errors if the schema is invalid or if we failed to execute any of its statements. In the latter case, the
+    /// statements executed until then are not undone.
+    pub fn apply_schema(&self, schema: &Schema) -> Result<(), Error> {
+        let stmts: Vec<Statement> = match schema.statements() {
+            Ok(stmts) => stmts,
+            Err(err) => return Err(Error::SchemaInvalid { err }),
+        };
+        info!("Applying schema of {} tables to SQLite database", schema.tables.len());
+        for stmt in stmts {
+            self.execute_prepared(stmt)?;
+        }
+        Ok(())
+    }
+
+    /// Copies this database to a file using SQLite's online backup API, while it is in use.
+    ///
+    /// The database is copied in steps of `pages_per_step` pages. This Database is only locked during a step, so it can be used in
+    /// between; writes through it are copied along as they happen. If another connection (e.g., in another process) writes to the
+    /// database, the backup restarts. If the database is locked by another connection, a step is retried for up to five seconds.
+    ///
+    /// Any existing database at `path` is overwritten.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the file to back up to.
+    /// - `pages_per_step`: The number of pages to copy per step. Use a small number to keep the database available to other
+    ///   connections, or [`usize::MAX`] to copy it in one go.
+    /// - `progress`: A closure that is called with the [`BackupProgress`] after every step.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open the file at `path` or to copy the database to it, e.g., because the database stayed
+    /// locked.
+    pub fn backup_to(&self, path: impl AsRef<Path>, pages_per_step: usize, mut progress: impl FnMut(&BackupProgress)) -> Result<(), Error> {
+        let path: &Path = path.as_ref();
+        info!("Backing up SQLite database to '{}'", path.display());
+        let raw_path: CString = match path.to_str().and_then(|path| CString::new(path).ok()) {
+            Some(path) => path,
+            None => {
+                return Err(Error::BackupFailed {
+                    path: path.into(),
+                    err:  sqlite::Error { code: None, message: Some("Path is not valid UTF-8 or contains a NUL-character".into()) },
+                });
+            },
+        };
+        let pages: c_int = pages_per_step.clamp(1, c_int::MAX as usize) as c_int;
+        let main: *const c_char = c"main".as_ptr();
+
+        // Open the destination
+        let mut dest: RawConnection = RawConnection(ptr::null_mut());
+        // SAFETY: The path is NUL-terminated and `dest` receives the connection, which is closed when it is dropped (even on error).
+        let flags: c_int = ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE;
+        let code: c_int = unsafe { ffi::sqlite3_open_v2(raw_path.as_ptr(), &mut dest.0, flags, ptr::null()) };
+        if code != ffi::SQLITE_OK {
+            return Err(Error::BackupFailed { path: path.into(), err: raw_error(dest.0, code) });
+        }
+
+        // Start the backup
+        // SAFETY: Both connections are open, and the backup is finished below before either is closed. The source connection is only
+        // used by the backup while we hold its lock.
+        let backup: *mut ffi::sqlite3_backup = unsafe { ffi::sqlite3_backup_init(dest.0, main, self.writer().as_raw(), main) };
+        if backup.is_null() {
+            // SAFETY: `dest` is open.
+            let code: c_int = unsafe { ffi::sqlite3_errcode(dest.0) };
+            return Err(Error::BackupFailed { path: path.into(), err: raw_error(dest.0, code) });
+        }
+
+        // Copy the pages step by step, only locking the source while stepping such that it can be used in between
+        let mut res: Result<(), sqlite::Error> = Ok(());
+        let mut retries: u32 = 0;
+        loop {
+            let (code, remaining, total): (c_int, c_int, c_int) = {
+                let _src: MutexGuard<Connection> = self.writer();
+                // SAFETY: The backup is initialized and not yet finished.
+                unsafe { (ffi::sqlite3_backup_step(backup, pages), ffi::sqlite3_backup_remaining(backup), ffi::sqlite3_backup_pagecount(backup)) }
+            };
+            match code {
+                ffi::SQLITE_OK | ffi::SQLITE_DONE => {
+                    retries = 0;
+                    progress(&BackupProgress { remaining: remaining.max(0) as u64, total: total.max(0) as u64 });
+                    if code == ffi::SQLITE_DONE {
+                        break;
+                    }
+                },
+                ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED if retries < BACKUP_BUSY_RETRIES => {
+                    retries += 1;
+                    thread::sleep(BACKUP_BUSY_SLEEP);
+                },
+                ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => {
+                    res = Err(sqlite::Error {
+                        code:    Some(code as isize),
+                        message: Some(format!("Database stayed locked after retrying {BACKUP_BUSY_RETRIES} times")),
+                    });
+                    break;
+                },
+                code => {
+                    res = Err(raw_error(dest.0, code));
+                    break;
+                },
+            }
+        }
+
+        // Finish it, which also reports any errors of the steps
+        let code: c_int = {
+            let _src: MutexGuard<Connection> = self.writer();
+            // SAFETY: The backup is initialized and not used after this.
+            unsafe { ffi::sqlite3_backup_finish(backup) }
+        };
+        if res.is_ok() && code != ffi::SQLITE_OK {
+            res = Err(raw_error(dest.0, code));
+        }
+        match res {
+            Ok(()) => {
+                debug!("Backed up SQLite database to '{}'", path.display());
+                Ok(())
+            },
+            Err(err) => Err(Error::BackupFailed { path: path.into(), err }),
+        }
+    }
+
+    /// Writes a compacted copy of this database to a file using `VACUUM INTO`.
+    ///
+    /// Unlike [`Database::backup_to()`], this copies the database in a single read transaction and leaves out unused pages, but it
+    /// blocks writes through this Database until it is done.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the file to write to. It must not exist yet (or be empty).
+    ///
+    /// # Errors
+    /// This function errors if the file at `path` already exists or if we failed to write the copy to it.
+    pub fn vacuum_into(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path: &Path = path.as_ref();
+        info!("Vacuuming SQLite database into '{}'", path.display());
+        let query: String = format!("VACUUM INTO {};", serialize_sql(&Value::String(path.to_string_lossy().into_owned()), Dialect::Sqlite));
+        match self.writer().execute(&query) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(Error::BackupFailed { path: path.into(), err }),
+        }
+    }
+
+    /// Checks that the database can be read by running a trivial query on it.
+    ///
+    /// This goes through a reader if the database is in WAL-mode, so it doesn't wait for writes (or transactions) to finish. Unlike
+    /// statements, it is never retried.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a reader or to run the query.
+    #[inline]
+    pub fn ping(&self) -> Result<(), Error> { query_raw_on(&*self.reader()?, PING_QUERY, &[]).map(|_| ()) }
+
+    /// Checks that the database can be read, and reports on it.
+    ///
+    /// Like `Self::ping()`, this goes through a reader if the database is in WAL-mode and is never retried.
+    ///
+    /// # Returns
+    /// A [`HealthReport`] with the time it took to run a query, the version of SQLite and the connections in use. The writer counts
+    /// as one of these, so the pool has at most `max_readers + 1` connections in WAL-mode and exactly one otherwise.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a reader or to run the query.
+    pub fn health(&self) -> Result<HealthReport, Error> {
+        let start: Instant = Instant::now();
+        let rows: Vec<Row> = query_raw_on(&*self.reader()?, HEALTH_QUERY, &[])?;
+        let latency: Duration = start.elapsed();
+        let server_version: String = rows.first().and_then(|row| row_str(row, 0)).unwrap_or_default().into();
+
+        // The writer is idle if nobody holds it, which includes us now that the reader is released
+        let idle_readers: usize = self.inner.readers.lock().unwrap_or_else(PoisonError::into_inner).len();
+        let idle_writer: bool = self.inner.writer.try_lock().is_ok();
+        let max_readers: usize = self.inner.source.as_ref().map(|(_, options)| options.max_readers).unwrap_or(0);
+        Ok(HealthReport {
+            latency,
+            server_version,
+            pool: PoolStats {
+                min_connections:  1,
+                max_connections:  1 + max_readers,
+                idle_connections: Some(idle_readers + usize::from(idle_writer)),
+            },
+        })
+    }
+
+    /// Runs the given closure in a transaction, and runs it again in a new transaction if it fails with a transient error.
+    ///
+    /// Unlike [`spec::Database::transaction()`], the closure may be called multiple times, so it should not have side effects outside
+    /// of the transaction. Whether and how often it is retried is determined by the [`RetryPolicy`] in the [`ConnectionOptions`]. The
+    /// writer is released between attempts.
+    ///
+    /// # Arguments
+    /// - `func`: The closure to run, which is given the [`Transaction`] to execute statements on.
+    ///
+    /// # Returns
+    /// The value returned by `func` in the attempt that succeeded.
+    ///
+    /// # Errors
+    /// This function errors if the last attempt fails, or if any attempt fails with an error that is not retried. Attempts may fail
+    /// because `func` errors or because we failed to begin or commit the transaction.
+    pub fn transaction_with_retry<'s, T, E>(&'s self, mut func: impl FnMut(&mut Transaction<'s>) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<Error> + Display + Retryable,
+    {
+        self.inner.retry.run(|| spec::Database::transaction(self, &mut func))
+    }
+
+    /// Claims the writer connection.
+    ///
+    /// If a thread panicked while holding it (e.g., in a transaction), any transaction it left open is rolled back first.
+    ///
+    /// # Returns
+    /// A [`MutexGuard`] that releases the writer when dropped.
+    fn writer(&self) -> MutexGuard<'_, Connection> {
+        match self.inner.writer.lock() {
+            Ok(conn) => conn,
+            Err(err) => {
+                warn!("Recovering SQLite connection after a panic; rolling back any open transaction");
+                let conn: MutexGuard<Connection> = err.into_inner();
+                // This fails if there is no transaction, which is fine
+                let _ = conn.execute("ROLLBACK;");
+                self.inner.writer.clear_poison();
+                conn
+            },
+        }
+    }
+
+    /// Claims a connection to read from.
+    ///
+    /// This is an idle reader from the pool, or a new one if there is none, if the database is in WAL-mode. Otherwise, it is the writer.
+    ///
+    /// # Returns
+    /// A [`ReadConn`] that releases the connection when dropped.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open a new reader.
+    fn reader(&self) -> Result<ReadConn<'_>, Error> {
+        let (path, options): &(PathBuf, ConnectionOptions) = match &self.inner.source {
+            Some(source) => source,
+            None => return Ok(ReadConn::Writer(self.writer())),
+        };
+        let idle: Option<Connection> = self.inner.readers.lock().unwrap_or_else(PoisonError::into_inner).pop();
+        let conn: Connection = match idle {
+            Some(conn) => conn,
+            None => {
+                debug!("Opening reader connection to '{}'...", path.display());
+                let conn: Connection = match Connection::open_with_flags(path, OpenFlags::new().with_read_only()) {
+                    Ok(conn) => conn,
+                    Err(err) => return Err(Error::DatabaseOpen { path: path.clone(), err }),
+                };
+                for pragma in options.pragmas(true) {
+                    execute_raw_on(&conn, &pragma)?;
+                }
+                conn
+            },
+        };
+        Ok(ReadConn::Pooled { conn: Some(conn), inner: &self.inner })
+    }
+}
+impl spec::Executor for Database {
+    type Error = Error;
+
+    const DIALECT: Dialect = Dialect::Sqlite;
+
+    #[inline]
+    fn execute(&mut self, stmt: &Statement) -> Result<ExecuteResult, Self::Error> { Database::execute(self, stmt) }
+
+    #[inline]
+    fn execute_prepared(&mut self, stmt: &Statement) -> Result<ExecuteResult, Self::Error> { Database::execute_prepared(self, stmt) }
+
+    #[inline]
+    fn query(&mut self, stmt: &Statement) -> Result<Vec<Row>, Self::Error> { Database::query(self, stmt) }
+
+    #[inline]
+    fn for_each_row<E>(&mut self, stmt: &Statement, func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+    where
+        E: From<Self::Error>,
+    {
+        Database::for_each_row(self, stmt, func)
+    }
+
+    #[inline]
+    fn execute_raw(&mut self, query: &str) -> Result<(), Self::Error> { Database::execute_raw(self, query) }
+
+    #[inline]
+    fn tables(&mut self) -> Result<Vec<Ident>, Self::Error> { Database::tables(self) }
+
+    #[inline]
+    fn describe_table(&mut self, table: &Ident) -> Result<StatementCreateTable, Self::Error> { Database::describe_table(self, table) }
+
+    #[inline]
+    fn indexes(&mut self, table: &Ident) -> Result<Vec<StatementCreateIndex>, Self::Error> { Database::indexes(self, table) }
+}
+impl spec::Database for Database {
+    type Transaction<'t> = Transaction<'t>;
+
+    fn transaction<'s, T, E>(&'s self, func: impl FnOnce(&mut Self::Transaction<'s>) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<Self::Error>,
+    {
+        // Claim the writer for the duration of the transaction
+        let conn: MutexGuard<Connection> = self.writer();
+        debug!("Beginning transaction");
+        if let Err(err) = conn.execute("BEGIN;") {
+            return Err(Error::TransactionBegin { err }.into());
+        }
+
+        // Run the closure, then commit or rollback depending on its result
+        let mut tx: Transaction = Transaction { conn };
+        match func(&mut tx) {
+            Ok(res) => {
+                debug!("Committing transaction");
+                if let Err(err) = tx.conn.execute("COMMIT;") {
+                    // Don't leave the transaction open
+                    if let Err(err) = tx.conn.execute("ROLLBACK;") {
+                        warn!("Failed to rollback transaction: {err}");
+                    }
+                    return Err(Error::TransactionCommit { err }.into());
+                }
+                Ok(res)
+            },
+            Err(err) => {
+                debug!("Rolling back transaction");
+                if let Err(err) = tx.conn.execute("ROLLBACK;") {
+                    warn!("Failed to rollback transaction: {err}");
+                }
+                Err(err)
+            },
+        }
+    }
+}
+
+
+
+/// A transaction on an SQLite [`Database`], as given by [`spec::Database::transaction()`].
+///
+/// The transaction holds the writer of the database, so any other writes through the Database (or its clones) wait until it is done.
+/// In particular, don't use the Database itself to write within the transaction, as this deadlocks.
+pub struct Transaction<'d> {
+    /// The writer on which the transaction runs.
+    conn: MutexGuard<'d, Connection>,
+}
+impl<'d> spec::Executor for Transaction<'d> {
+    type Error = Error;
+
+    const DIALECT: Dialect = Dialect::Sqlite;
+
+    #[inline]
+    fn execute(&mut self, stmt: &Statement) -> Result<ExecuteResult, Self::Error> { execute_on(&self.conn, stmt) }
+
+    #[inline]
+    fn execute_prepared(&mut self, stmt: &Statement) -> Result<ExecuteResult, Self::Error> { execute_prepared_on(&self.conn, stmt) }
+
+    fn query(&mut self, stmt: &Statement) -> Result<Vec<Row>, Self::Error> {
+        let mut rows: Vec<Row> = vec![];
+        for_each_row_on(&self.conn, stmt, |row| -> Result<(), Error> {
+            rows.push(row);
+            Ok(())
+        })?;
+        Ok(rows)
+    }
+
+    #[inline]
+    fn for_each_row<E>(&mut self, stmt: &Statement, func: impl FnMut(Row) -> Result<(), E>) -> Result<(), E>
+    where
+        E: From<Self::Error>,
+    {
+        for_each_row_on(&self.conn, stmt, func)
+    }
+
+    #[inline]
+    fn execute_raw(&mut self, query: &str) -> Result<(), Self::Error> { execute_raw_on(&self.conn, query) }
+
+    #[inline]
+    fn tables(&mut self) -> Result<Vec<Ident>, Self::Error> { tables_on(&self.conn) }
+
+    #[inline]
+    fn describe_table(&mut self, table: &Ident) -> Result<StatementCreateTable, Self::Error> { describe_table_on(&self.conn, table) }
+
+    #[inline]
+    fn indexes(&mut self, table: &Ident) -> Result<Vec<StatementCreateIndex>, Self::Error> { indexes_on(&self.conn, table) }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::{StatementDelete, StatementInsert, StatementSelect};
+
+
+    /// Returns a path for a database file unique to this process, removing any files left at it by an earlier run.
+    fn temp_path(name: &str) -> PathBuf {
+        let path: PathBuf = std::env::temp_dir().join(format!("database-sqlite-test-{}-{name}", process::id()));
+        for suffix in ["", "-journal", "-wal", "-shm"] {
+            let mut path: PathBuf = path.clone();
+            path.as_mut_os_string().push(suffix);
+            let _ = fs::remove_file(path);
+        }
+        path
+    }
+
+    /// Creates a table `t` with the given number of rows, each large enough to fill a good part of a page.
+    fn fill(db: &Database, rows: usize) -> Result<(), Error> {
+        db.execute_raw("CREATE TABLE IF NOT EXISTS t (id INTEGER PRIMARY KEY, data TEXT NOT NULL);")?;
+        let rows: Vec<Vec<Value>> = (0..rows).map(|_| vec![Value::String("x".repeat(1000))]).collect();
+        let insert: StatementInsert = StatementInsert { rows, ..StatementInsert::new("t", ["data"]) };
+        db.execute_prepared(Statement::Insert(insert)).map(|_| ())
+    }
+
+    /// Reads the value of a pragma on the given connection, formatted for comparison.
+    fn pragma(conn: &Connection, name: &str) -> String {
+        let rows: Vec<Row> = query_raw_on(conn, &format!("PRAGMA {name};"), &[]).unwrap();
+        format!("{:?}", rows[0].values[0])
+    }
+
+    /// Counts the rows in table `t`.
+    fn count(db: &Database) -> usize { db.query(Statement::Select(StatementSelect::new("t"))).unwrap().len() }
+
+    #[test]
+    fn url_params() {
+        let url: &str = "sqlite:///var/lib/app.db?journal_mode=wal&pragma.temp_store=memory&pragma.cache_spill=0";
+        let config: ConfigFile = ConfigFile::from_url(url).unwrap();
+        assert_eq!(config.path, Path::new("/var/lib/app.db"));
+        assert!(config.options.journal_mode.is_some());
+        assert!(matches!(config.options.pragmas.get(&Ident::new("temp_store")), Some(Value::String(s)) if s == "memory"));
+        assert!(matches!(config.options.pragmas.get(&Ident::new("cache_spill")), Some(Value::BigInt(0))));
+
+        // Unknown parameters are not taken as pragmas
+        assert!(matches!(ConfigFile::from_url("sqlite:app.db?journal_mod=wal"), Err(Error::UrlInvalid { .. })));
+        assert!(matches!(ConfigFile::from_url("sqlite:app.db?pragma.=1"), Err(Error::UrlInvalid { .. })));
+    }
+
+    #[test]
+    fn returning() {
+        let db: Database = Database::in_memory(|db| db.execute_raw("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);")).unwrap();
+        let insert: StatementInsert =
+            StatementInsert::new("t", ["name"]).add_row([Value::String("a".into())]).add_row([Value::String("b".into())]).returning(["id"]);
+        let rows: Vec<Row> = db.query(Statement::Insert(insert)).unwrap();
+        let ids: Vec<String> = rows.iter().map(|row| format!("{:?}", row.values)).collect();
+        assert_eq!(ids, ["[BigInt(1)]", "[BigInt(2)]"]);
+
+        let delete: StatementDelete = StatementDelete::new("t").returning(["name"]);
+        assert_eq!(db.query(Statement::Delete(delete)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn backup_to_file() {
+        let src: Database = Database::new(temp_path("backup-src.db"), |db| fill(db, 100)).unwrap();
+        let dst: PathBuf = temp_path("backup-dst.db");
+
+        // Copy it in small steps, which are all reported
+        let mut steps: Vec<BackupProgress> = vec![];
+        src.backup_to(&dst, 4, |progress| steps.push(*progress)).unwrap();
+        assert!(steps.len() > 1, "{steps:?}");
+        assert!(steps.iter().all(|step| step.total > 0 && step.remaining <= step.total), "{steps:?}");
+        assert!(steps.windows(2).all(|w| w[1].remaining <= w[0].remaining), "{steps:?}");
+        assert_eq!(steps.last().map(|step| step.remaining), Some(0));
+        assert_eq!(count(&Database::new(&dst, |_| Ok(())).unwrap()), 100);
+
+        // Backing up again overwrites the copy
+        fill(&src, 10).unwrap();
+        src.backup_to(&dst, usize::MAX, |_| {}).unwrap();
+        assert_eq!(count(&Database::new(&dst, |_| Ok(())).unwrap()), 110);
+
+        // Failing to open the destination is an error
+        assert!(matches!(src.backup_to(temp_path("missing-dir").join("backup.db"), 4, |_| {}), Err(Error::BackupFailed { .. })));
+    }
+
+    #[test]
+    fn vacuum_into_file() {
+        let src: Database = Database::new(temp_path("vacuum-src.db"), |db| fill(db, 100)).unwrap();
+        let dst: PathBuf = temp_path("vacuum-dst.db");
+        src.vacuum_into(&dst).unwrap();
+        assert_eq!(count(&Database::new(&dst, |_| Ok(())).unwrap()), 100);
+
+        // An existing database is not overwritten
+        fill(&src, 10).unwrap();
+        assert!(matches!(src.vacuum_into(&dst), Err(Error::BackupFailed { path, .. }) if path == dst));
+        assert_eq!(count(&Database::new(&dst, |_| Ok(())).unwrap()), 100);
+    }
+
+    #[test]
+    fn in_memory_init() {
+        let db: Database = Database::in_memory(|db| fill(db, 3)).unwrap();
+        assert_eq!(count(&db), 3);
+
+        // Every in-memory database is its own
+        assert!(Database::in_memory(|_| Ok(())).unwrap().tables().unwrap().is_empty());
+
+        // Errors in the init are returned
+        assert!(Database::in_memory(|db| db.execute_raw("NOT SQL;")).is_err());
+    }
+
+    #[test]
+    fn in_memory_shared_by_name() {
+        let name: String = format!("shared-{}", process::id());
+        let first: Database = Database::in_memory_shared(&name, |db| fill(db, 3)).unwrap();
+
+        // The second handle sees the same data, and doesn't run its init
+        let second: Database = Database::in_memory_shared(&name, |_| panic!("init ran twice")).unwrap();
+        assert_eq!(count(&second), 3);
+        fill(&second, 2).unwrap();
+        assert_eq!(count(&first), 5);
+
+        // Other names are other databases
+        assert!(Database::in_memory_shared(format!("{name}-other"), |_| Ok(())).unwrap().tables().unwrap().is_empty());
+    }
+
+    #[test]
+    fn temporary_removes_files() {
+        let db: Database = Database::temporary(|db| {
+            db.execute_raw("PRAGMA journal_mode = WAL;")?;
+            fill(db, 3)
+        })
+        .unwrap();
+        assert_eq!(count(&db), 3);
+        let path: PathBuf = db.inner._temp.as_ref().map(|temp| temp.0.clone()).unwrap();
+        let files: Vec<PathBuf> = ["", "-wal", "-shm"]
+            .into_iter()
+            .map(|suffix| {
+                let mut path: PathBuf = path.clone();
+                path.as_mut_os_string().push(suffix);
+                path
+            })
+            .collect();
+        assert!(files.iter().all(|file| file.exists()), "{files:?}");
+
+        // They're all gone once the last clone is dropped
+        let clone: Database = db.clone();
+        drop(db);
+        assert!(files[0].exists());
+        drop(clone);
+        assert!(files.iter().all(|file| !file.exists()), "{files:?}");
+    }
+
+    #[test]
+    fn options_set_pragmas() {
+        let options: ConnectionOptions = ConnectionOptions {
+            journal_mode: Some(JournalMode::Wal),
+            synchronous: Some(Synchronous::Normal),
+            busy_timeout: Some(1234),
+            foreign_keys: Some(true),
+            cache_size: Some(-4000),
+            pragmas: [(Ident::new("temp_store"), Value::String("memory".into()))].into(),
+            ..Default::default()
+        };
+        let db: Database = Database::with_options(temp_path("pragmas.db"), &options, |db| {
+            db.execute_raw("CREATE TABLE parent (id INTEGER PRIMARY KEY); CREATE TABLE child (parent INTEGER REFERENCES parent (id));")
+        })
+        .unwrap();
+
+        // The writer has all of them
+        {
+            let writer: MutexGuard<Connection> = db.writer();
+            assert_eq!(pragma(&writer, "journal_mode"), "String(\"wal\")");
+            assert_eq!(pragma(&writer, "synchronous"), "BigInt(1)");
+            assert_eq!(pragma(&writer, "busy_timeout"), "BigInt(1234)");
+            assert_eq!(pragma(&writer, "foreign_keys"), "BigInt(1)");
+            assert_eq!(pragma(&writer, "cache_size"), "BigInt(-4000)");
+            assert_eq!(pragma(&writer, "temp_store"), "BigInt(2)");
+        }
+
+        // Readers have those that are per connection
+        {
+            let reader: ReadConn = db.reader().unwrap();
+            assert!(matches!(reader, ReadConn::Pooled { .. }));
+            assert_eq!(pragma(&reader, "busy_timeout"), "BigInt(1234)");
+            assert_eq!(pragma(&reader, "foreign_keys"), "BigInt(1)");
+            assert_eq!(pragma(&reader, "temp_store"), "BigInt(2)");
+        }
+
+        // And they take effect
+        let err: Error = db.execute_raw("INSERT INTO child (parent) VALUES (42);").unwrap_err();
+        assert!(err.is_constraint_violation(), "{err:?}");
+    }
+
+    #[test]
+    fn url_sets_pragmas() {
+        let path: PathBuf = temp_path("url-pragmas.db");
+        let url: String = format!("sqlite://{}?journal_mode=wal&busy_timeout=250&foreign_keys=on&pragma.user_version=7", path.display());
+        let db: Database = Database::from_url(&url, |_| Ok(())).unwrap();
+        let writer: MutexGuard<Connection> = db.writer();
+        assert_eq!(pragma(&writer, "journal_mode"), "String(\"wal\")");
+        assert_eq!(pragma(&writer, "busy_timeout"), "BigInt(250)");
+        assert_eq!(pragma(&writer, "foreign_keys"), "BigInt(1)");
+        assert_eq!(pragma(&writer, "user_version"), "BigInt(7)");
+    }
+
+    #[test]
+    fn wal_reads_across_threads() {
+        use std::sync::mpsc::{self, Receiver, Sender};
+        use std::sync::Barrier;
+
+        use crate::spec::Executor as _;
+
+        let options: ConnectionOptions = ConnectionOptions { journal_mode: Some(JournalMode::Wal), max_readers: 2, ..Default::default() };
+        let db: Database = Database::with_options(temp_path("wal-threads.db"), &options, |db| fill(db, 1)).unwrap();
+
+        // Reads are served while another thread has a write transaction open, and don't see its changes until it commits
+        let (started, wait_started): (Sender<()>, Receiver<()>) = mpsc::channel();
+        let (commit, wait_commit): (Sender<()>, Receiver<()>) = mpsc::channel();
+        let writer: thread::JoinHandle<()> = thread::spawn({
+            let db: Database = db.clone();
+            move || {
+                spec::Database::transaction(&db, |tx| -> Result<(), Error> {
+                    let insert: StatementInsert = StatementInsert::new("t", ["data"]).add_row([Value::String("uncommitted".into())]);
+                    tx.execute(&Statement::Insert(insert))?;
+                    started.send(()).unwrap();
+                    wait_commit.recv().unwrap();
+                    Ok(())
+                })
+                .unwrap()
+            }
+        });
+        wait_started.recv().unwrap();
+        assert_eq!(count(&db), 1);
+        commit.send(()).unwrap();
+        writer.join().unwrap();
+        assert_eq!(count(&db), 2);
+
+        // Many clones can read concurrently while one of them writes
+        let barrier: Arc<Barrier> = Arc::new(Barrier::new(5));
+        let mut handles: Vec<thread::JoinHandle<()>> = vec![];
+        for _ in 0..4 {
+            let (db, barrier): (Database, Arc<Barrier>) = (db.clone(), barrier.clone());
+            handles.push(thread::spawn(move || {
+                barrier.wait();
+                let mut last: usize = 0;
+                for _ in 0..50 {
+                    let n: usize = count(&db);
+                    assert!((last..=52).contains(&n), "count went from {last} to {n}");
+                    last = n;
+                }
+            }));
+        }
+        let (db2, barrier2): (Database, Arc<Barrier>) = (db.clone(), barrier.clone());
+        handles.push(thread::spawn(move || {
+            barrier2.wait();
+            for _ in 0..50 {
+                fill(&db2, 1).unwrap();
+            }
+        }));
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(count(&db), 52);
+
+        // The readers went back to the pool, which is capped
+        let idle: usize = db.inner.readers.lock().unwrap().len();
+        assert!((1..=2).contains(&idle), "{idle} idle readers");
+    }
+
+    #[test]
+    fn datetime_round_trip() {
+        use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone as _};
+
+        use crate::spec::cast_to_columns;
+        use crate::sql::{ColumnDef, StatementCreateTable, Type};
+
+        let date: NaiveDate = NaiveDate::from_ymd_opt(2023, 12, 30).unwrap();
+        let local: NaiveDateTime = date.and_hms_micro_opt(1, 2, 3, 4).unwrap();
+        let row: Vec<Value> = vec![
+            Value::Date(date),
+            Value::Time(NaiveTime::from_hms_milli_opt(12, 13, 1, 250).unwrap()),
+            Value::DateTime(date.and_hms_micro_opt(12, 13, 1, 123456).unwrap()),
+            Value::DateTimeTz(FixedOffset::east_opt(5 * 3600 + 1800).unwrap().from_local_datetime(&local).unwrap()),
+        ];
+        let def: StatementCreateTable = StatementCreateTable::new("t")
+            .add_col(ColumnDef::new("d", Type::Date))
+            .add_col(ColumnDef::new("t", Type::Time(3)))
+            .add_col(ColumnDef::new("dt", Type::DateTime(6)))
+            .add_col(ColumnDef::new("tz", Type::DateTime(6)));
+        let db: Database = Database::in_memory(|db| db.execute(Statement::CreateTable(def.clone())).map(|_| ())).unwrap();
+
+        // Both as literals and as parameters
+        let insert: StatementInsert = StatementInsert::new("t", def.cols.iter().map(|c| &c.name)).add_row(row.clone());
+        db.execute(Statement::Insert(insert.clone())).unwrap();
+        db.execute_prepared(Statement::Insert(insert)).unwrap();
 
-        // Serialize directly and send
-        let query: String = serialize_sql(stmt).to_string();
-        match self.conn.execute(&query) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(Error::ExecuteFailed { query, err }),
+        let described: StatementCreateTable = db.describe_table(&Ident::new("t")).unwrap();
+        for back in db.query(Statement::Select(StatementSelect::new("t"))).unwrap() {
+            let back: Vec<Value> = cast_to_columns(back.values, &described.cols);
+            assert_eq!(format!("{back:?}"), format!("{row:?}"));
         }
     }
 }